@@ -0,0 +1,157 @@
+use std::io;
+
+use crate::code_block::longest_run;
+use crate::{Flavor, MarkdownWriter};
+
+/// The kinds of GitHub alerts, Docusaurus' admonitions are named differently for some of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdmonitionKind {
+    Note,
+    Tip,
+    Important,
+    Warning,
+    Caution,
+}
+
+impl AdmonitionKind {
+    fn title(self) -> &'static str {
+        match self {
+            AdmonitionKind::Note => "Note",
+            AdmonitionKind::Tip => "Tip",
+            AdmonitionKind::Important => "Important",
+            AdmonitionKind::Warning => "Warning",
+            AdmonitionKind::Caution => "Caution",
+        }
+    }
+
+    fn directive(self) -> &'static str {
+        match self {
+            AdmonitionKind::Note => "note",
+            AdmonitionKind::Tip => "tip",
+            AdmonitionKind::Important => "info",
+            AdmonitionKind::Warning => "warning",
+            AdmonitionKind::Caution => "danger",
+        }
+    }
+}
+
+impl<T> MarkdownWriter<T>
+where
+    T: io::Write,
+{
+    /// Writes `content` as a callout: a `> [!NOTE]` alert for GFM, a `:::note` block for MDX and
+    /// mdBook and a blockquote starting with the bold title for CommonMark. `content` isn't
+    /// escaped, so it can contain any markdown including other admonitions.
+    pub fn admonition(&mut self, kind: AdmonitionKind, content: &str) -> io::Result<()> {
+        let content = content.trim_end();
+        match self.flavor {
+            Flavor::Gfm => {
+                writeln!(self.writer, "> [!{}]", kind.title().to_uppercase())?;
+                self.write_quoted(content)
+            }
+            Flavor::CommonMark => {
+                writeln!(self.writer, "> **{}**\n>", kind.title())?;
+                self.write_quoted(content)
+            }
+            Flavor::Mdx | Flavor::MdBook => {
+                // Nested admonitions are closed by the first fence that's at least as long as
+                // theirs, so the outer one has to be longer
+                let fence = ":".repeat(longest_run(content, ':').max(2) + 1);
+                writeln!(self.writer, "{fence}{}", kind.directive())?;
+                writeln!(self.writer, "{content}")?;
+                writeln!(self.writer, "{fence}")
+            }
+        }
+    }
+
+    // Every line needs the marker, or it would be a lazy continuation line at best
+    fn write_quoted(&mut self, content: &str) -> io::Result<()> {
+        for line in content.lines() {
+            if line.is_empty() {
+                writeln!(self.writer, ">")?;
+            } else {
+                writeln!(self.writer, "> {line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AdmonitionKind;
+    use crate::{Flavor, MarkdownWriter};
+
+    fn write(flavor: Flavor, kind: AdmonitionKind, content: &str) -> Vec<String> {
+        let mut writer = MarkdownWriter::with_flavor(Vec::new(), flavor);
+        writer.admonition(kind, content).unwrap();
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        output.lines().map(str::to_string).collect()
+    }
+
+    const BODY: &str = "First line\n\n- item\n  continued\n";
+
+    #[test]
+    fn gfm() {
+        assert_eq!(
+            write(Flavor::Gfm, AdmonitionKind::Important, BODY),
+            [
+                "> [!IMPORTANT]",
+                "> First line",
+                ">",
+                "> - item",
+                ">   continued"
+            ]
+        );
+    }
+
+    #[test]
+    fn commonmark() {
+        assert_eq!(
+            write(Flavor::CommonMark, AdmonitionKind::Caution, BODY),
+            [
+                "> **Caution**",
+                ">",
+                "> First line",
+                ">",
+                "> - item",
+                ">   continued"
+            ]
+        );
+    }
+
+    #[test]
+    fn directives() {
+        for flavor in [Flavor::Mdx, Flavor::MdBook] {
+            assert_eq!(
+                write(flavor, AdmonitionKind::Warning, BODY),
+                [
+                    ":::warning",
+                    "First line",
+                    "",
+                    "- item",
+                    "  continued",
+                    ":::"
+                ]
+            );
+        }
+        // Named after Docusaurus' admonitions rather than GitHub's alerts
+        assert_eq!(
+            write(Flavor::Mdx, AdmonitionKind::Important, "a")[0],
+            ":::info"
+        );
+        assert_eq!(
+            write(Flavor::Mdx, AdmonitionKind::Caution, "a")[0],
+            ":::danger"
+        );
+    }
+
+    #[test]
+    fn nested_directives_get_a_longer_fence() {
+        let inner = write(Flavor::Mdx, AdmonitionKind::Tip, "a").join("\n");
+        assert_eq!(
+            write(Flavor::Mdx, AdmonitionKind::Note, &inner),
+            ["::::note", ":::tip", "a", ":::", "::::"]
+        );
+    }
+}
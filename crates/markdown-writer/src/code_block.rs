@@ -0,0 +1,146 @@
+use std::{fmt, io};
+
+use crate::MarkdownWriter;
+
+/// The info string after the opening fence of a code block, e.g. `rust,ignore title="main.rs"`
+#[derive(Debug, Clone, Default)]
+pub struct InfoString {
+    pub language: Option<String>,
+    /// Comma separated after the language, like rustdoc's `ignore` or `should_panic`
+    pub flags: Vec<String>,
+    /// Space separated `key="value"` pairs, like the `title` many site generators support
+    pub attributes: Vec<(String, String)>,
+}
+
+impl InfoString {
+    pub fn new(language: Option<&str>) -> Self {
+        Self {
+            language: language.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    pub fn flag(mut self, flag: impl Into<String>) -> Self {
+        self.flags.push(flag.into());
+        self
+    }
+
+    pub fn attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.push((key.into(), value.into()));
+        self
+    }
+}
+
+impl fmt::Display for InfoString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut words = self.language.iter().chain(&self.flags).peekable();
+        while let Some(word) = words.next() {
+            f.write_str(word)?;
+            if words.peek().is_some() {
+                f.write_str(",")?;
+            }
+        }
+        for (i, (key, value)) in self.attributes.iter().enumerate() {
+            if i > 0 || self.language.is_some() || !self.flags.is_empty() {
+                f.write_str(" ")?;
+            }
+            write!(f, "{key}=\"{}\"", value.replace('"', "\\\""))?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> MarkdownWriter<T>
+where
+    T: io::Write,
+{
+    /// Like [`Self::fenced_code_block`], but with a full info string
+    pub fn fenced_code_block_with_info(&mut self, code: &str, info: &InfoString) -> io::Result<()> {
+        let info = info.to_string();
+        // Backtick fences can't have backticks in the info string
+        let fence_char = if info.contains('`') { '~' } else { '`' };
+        // The closing fence has to be at least as long as the opening one, so a fence that's
+        // longer than any run in the code can't be closed early
+        let fence = fence_char
+            .to_string()
+            .repeat(longest_run(code, fence_char).max(2) + 1);
+        write!(self.writer, "{fence}{info}\n{code}\n{fence}")
+    }
+}
+
+pub(crate) fn longest_run(text: &str, c: char) -> usize {
+    let mut longest = 0;
+    let mut run = 0;
+    for text_char in text.chars() {
+        if text_char == c {
+            run += 1;
+            longest = longest.max(run);
+        } else {
+            run = 0;
+        }
+    }
+    longest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InfoString;
+    use crate::MarkdownWriter;
+
+    fn write(code: &str, info: &InfoString) -> Vec<String> {
+        let mut writer = MarkdownWriter::new(Vec::new());
+        writer.fenced_code_block_with_info(code, info).unwrap();
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        output.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn info_string() {
+        let info = InfoString::new(Some("rust"))
+            .flag("ignore")
+            .flag("edition2021")
+            .attribute("title", r#"say "hi""#);
+        assert_eq!(
+            info.to_string(),
+            r#"rust,ignore,edition2021 title="say \"hi\"""#
+        );
+        assert_eq!(
+            InfoString::new(None)
+                .attribute("a", "1")
+                .attribute("b", "2")
+                .to_string(),
+            r#"a="1" b="2""#
+        );
+        assert_eq!(InfoString::new(None).to_string(), "");
+    }
+
+    #[test]
+    fn fence_is_longer_than_backtick_runs() {
+        let rust = InfoString::new(Some("rust"));
+        assert_eq!(write("let a = 1;", &rust), ["```rust", "let a = 1;", "```"]);
+        // Shorter runs don't need a longer fence
+        assert_eq!(
+            write("a `b` ``c``", &rust),
+            ["```rust", "a `b` ``c``", "```"]
+        );
+        assert_eq!(
+            write("```\nnested\n```", &rust),
+            ["````rust", "```", "nested", "```", "````"]
+        );
+        assert_eq!(write("``````", &rust), ["```````rust", "``````", "```````"]);
+    }
+
+    #[test]
+    fn backticks_in_info_string_use_tildes() {
+        let info = InfoString::new(Some("rust")).attribute("title", "`main.rs`");
+        assert_eq!(
+            write("let a = 1;", &info),
+            [r#"~~~rust title="`main.rs`""#, "let a = 1;", "~~~"]
+        );
+        // Then it's the tilde runs in the code that matter
+        assert_eq!(
+            write("```\n~~~", &info),
+            [r#"~~~~rust title="`main.rs`""#, "```", "~~~", "~~~~"]
+        );
+    }
+}
@@ -0,0 +1,243 @@
+use linkify::LinkFinder;
+
+use crate::Flavor;
+
+// ASCII punctuation that can start or end inline markdown, or a block at the start of a line. Any
+// ASCII punctuation can be escaped with a backslash in CommonMark, so escaping too much is safe.
+const SPECIAL_CHARS: &[char] = &[
+    '\\', '`', '*', '_', '{', '}', '[', ']', '(', ')', '<', '>', '#', '+', '-', '!', '~', '|', '&',
+];
+
+/// Escapes `text` so it's rendered literally. URLs are kept as they are if the flavor links them
+/// automatically, otherwise they're wrapped in `<>` so they're still linked.
+///
+/// ```
+/// use markdown_writer::{Flavor, escape_text};
+///
+/// assert_eq!(
+///     escape_text("Vec<T> at https://doc.rust-lang.org/std/vec/", Flavor::Gfm),
+///     r"Vec\<T\> at https://doc.rust-lang.org/std/vec/"
+/// );
+/// assert_eq!(
+///     escape_text(
+///         "Vec<T> at https://doc.rust-lang.org/std/vec/",
+///         Flavor::CommonMark
+///     ),
+///     r"Vec\<T\> at <https://doc.rust-lang.org/std/vec/>"
+/// );
+/// ```
+pub fn escape_text(text: &str, flavor: Flavor) -> String {
+    if flavor.links_bare_urls() {
+        escape_with_urls(text, |url, escaped| escaped.push_str(url))
+    } else {
+        escape_with_urls(text, |url, escaped| {
+            escaped.push('<');
+            escaped.push_str(url);
+            escaped.push('>');
+        })
+    }
+}
+
+// Links can't contain other links and bare URLs aren't linked inside link text, so URLs are
+// escaped like the rest of the text in every flavor
+pub(crate) fn escape_link_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    escape_chars(text, &mut escaped);
+    escaped
+}
+
+fn escape_with_urls(text: &str, write_url: impl Fn(&str, &mut String)) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut pos = 0;
+    for link in LinkFinder::new().links(text) {
+        escape_chars(&text[pos..link.start()], &mut escaped);
+        write_url(link.as_str(), &mut escaped);
+        pos = link.end();
+    }
+    escape_chars(&text[pos..], &mut escaped);
+    escaped
+}
+
+fn escape_chars(text: &str, escaped: &mut String) {
+    for line in text.split_inclusive('\n') {
+        let mut rest = line;
+        if escaped.is_empty() || escaped.ends_with('\n') {
+            rest = escape_line_start(line, escaped);
+        }
+        for c in rest.chars() {
+            if SPECIAL_CHARS.contains(&c) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+    }
+}
+
+// Blocks that start with characters that are only special at the start of a line, like the `.` in
+// an ordered list item such as `1. foo`, or a setext heading underline like `===`. Returns the
+// rest of the line.
+fn escape_line_start<'a>(line: &'a str, escaped: &mut String) -> &'a str {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    let after_indent = &line[indent..];
+    let digits = after_indent.len()
+        - after_indent
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .len();
+    // List items can start with at most 9 digits, the `)` form is escaped as a special character
+    let marker = if (1..=9).contains(&digits) && after_indent[digits..].starts_with('.') {
+        indent + digits
+    } else if after_indent.starts_with('=') {
+        indent
+    } else {
+        return line;
+    };
+    escaped.push_str(&line[..marker]);
+    escaped.push('\\');
+    &line[marker..]
+}
+
+/// Formats `destination` for use in `[text](destination)`. Destinations with spaces, angle
+/// brackets or parentheses are wrapped in `<>`, which allows them in CommonMark. MDX would read
+/// the `<` as JSX, so those characters are percent-encoded or escaped there instead.
+///
+/// ```
+/// use markdown_writer::{Flavor, escape_destination};
+///
+/// assert_eq!(
+///     escape_destination("https://example.com/a b", Flavor::Gfm),
+///     "<https://example.com/a b>"
+/// );
+/// assert_eq!(
+///     escape_destination("https://example.com/a b", Flavor::Mdx),
+///     "https://example.com/a%20b"
+/// );
+/// assert_eq!(escape_destination("#anchor", Flavor::Gfm), "#anchor");
+/// ```
+pub fn escape_destination(destination: &str, flavor: Flavor) -> String {
+    // Line breaks aren't allowed in either form
+    let destination = destination.replace('\r', "%0D").replace('\n', "%0A");
+    if flavor == Flavor::Mdx {
+        let mut escaped = String::with_capacity(destination.len());
+        for c in destination.chars() {
+            match c {
+                '<' => escaped.push_str("%3C"),
+                '>' => escaped.push_str("%3E"),
+                '\\' | '(' | ')' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                _ if c.is_whitespace() || c.is_control() => {
+                    let mut buf = [0; 4];
+                    for byte in c.encode_utf8(&mut buf).bytes() {
+                        escaped.push_str(&format!("%{byte:02X}"));
+                    }
+                }
+                _ => escaped.push(c),
+            }
+        }
+        return escaped;
+    }
+    let needs_brackets = destination.is_empty()
+        || destination
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '<' | '>' | '(' | ')'));
+    if !needs_brackets {
+        // Backslashes escape punctuation in both forms
+        return destination.replace('\\', "\\\\");
+    }
+    let mut escaped = String::with_capacity(destination.len() + 2);
+    escaped.push('<');
+    for c in destination.chars() {
+        if matches!(c, '\\' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped.push('>');
+    escaped
+}
+
+/// Formats `title` as a quoted link title, e.g. for `[text](destination "title")`
+pub fn escape_title(title: &str) -> String {
+    let mut escaped = String::with_capacity(title.len() + 2);
+    escaped.push('"');
+    for c in title.chars() {
+        if matches!(c, '\\' | '"') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_destination, escape_link_text, escape_text, escape_title};
+    use crate::Flavor;
+
+    #[test]
+    fn escapes_inline_markup() {
+        assert_eq!(
+            escape_text("*a* _b_ `c` [d](e) <f> &g; ~h~ a|b", Flavor::Gfm),
+            r"\*a\* \_b\_ \`c\` \[d\]\(e\) \<f\> \&g; \~h\~ a\|b"
+        );
+        assert_eq!(escape_text(r"a\b", Flavor::Gfm), r"a\\b");
+    }
+
+    #[test]
+    fn escapes_block_starts() {
+        assert_eq!(escape_text("# a", Flavor::Gfm), r"\# a");
+        assert_eq!(escape_text("> a", Flavor::Gfm), r"\> a");
+        assert_eq!(escape_text("- a\n+ b", Flavor::Gfm), "\\- a\n\\+ b");
+        assert_eq!(escape_text("1. foo", Flavor::Gfm), r"1\. foo");
+        assert_eq!(escape_text("1) foo", Flavor::Gfm), r"1\) foo");
+        assert_eq!(escape_text("a\n  12. b", Flavor::Gfm), "a\n  12\\. b");
+        assert_eq!(escape_text("a\n===", Flavor::Gfm), "a\n\\===");
+        assert_eq!(escape_text("a\n---", Flavor::Gfm), "a\n\\-\\-\\-");
+        // Only at the start of a line
+        assert_eq!(
+            escape_text("version 1. a = b", Flavor::Gfm),
+            "version 1. a = b"
+        );
+        // Too many digits for a list item
+        assert_eq!(escape_text("1234567890. a", Flavor::Gfm), "1234567890. a");
+    }
+
+    #[test]
+    fn keeps_urls() {
+        let text = "see https://example.com/a_b*c, then *d*";
+        assert_eq!(
+            escape_text(text, Flavor::Gfm),
+            r"see https://example.com/a_b*c, then \*d\*"
+        );
+        assert_eq!(
+            escape_text(text, Flavor::CommonMark),
+            r"see <https://example.com/a_b*c>, then \*d\*"
+        );
+        assert_eq!(
+            escape_link_text(text),
+            r"see https://example.com/a\_b\*c, then \*d\*"
+        );
+    }
+
+    #[test]
+    fn escapes_destinations() {
+        assert_eq!(escape_destination("a/b.md#c", Flavor::Gfm), "a/b.md#c");
+        assert_eq!(escape_destination(r"a\*b", Flavor::Gfm), r"a\\*b");
+        assert_eq!(escape_destination("", Flavor::Gfm), "<>");
+        assert_eq!(escape_destination("a b", Flavor::Gfm), "<a b>");
+        assert_eq!(escape_destination(r"a(<\>)", Flavor::Gfm), r"<a(\<\\\>)>");
+        assert_eq!(escape_destination("a\nb", Flavor::Gfm), "a%0Ab");
+        assert_eq!(
+            escape_destination(r"a <(\)>", Flavor::Mdx),
+            r"a%20%3C\(\\\)%3E"
+        );
+    }
+
+    #[test]
+    fn escapes_titles() {
+        assert_eq!(escape_title("a"), r#""a""#);
+        assert_eq!(escape_title(r#"a "b" \c"#), r#""a \"b\" \\c""#);
+    }
+}
@@ -0,0 +1,47 @@
+/// The markdown dialect the output is written for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Flavor {
+    /// GitHub Flavored Markdown, with tables and bare URLs linked automatically
+    #[default]
+    Gfm,
+    /// Plain CommonMark, which has no tables or bare URL links
+    CommonMark,
+    /// MDX as used by Docusaurus. `<` and `{` start JSX and expressions so they're always
+    /// escaped, and HTML comments aren't allowed.
+    Mdx,
+    /// mdBook's dialect, which supports tables and header attributes but doesn't link bare URLs
+    MdBook,
+}
+
+impl Flavor {
+    /// Whether URLs in text are linked without `<>` around them
+    pub fn links_bare_urls(self) -> bool {
+        matches!(self, Flavor::Gfm | Flavor::Mdx)
+    }
+
+    pub fn supports_tables(self) -> bool {
+        !matches!(self, Flavor::CommonMark)
+    }
+
+    /// Whether custom anchors can be set with `{#anchor}` after the header text
+    pub fn supports_header_attributes(self) -> bool {
+        matches!(self, Flavor::Mdx | Flavor::MdBook)
+    }
+
+    /// A line break inside a block like a table cell. MDX only accepts self-closing JSX tags.
+    pub fn line_break(self) -> &'static str {
+        match self {
+            Flavor::Mdx => "<br />",
+            Flavor::Gfm | Flavor::CommonMark | Flavor::MdBook => "<br>",
+        }
+    }
+
+    /// Marks where [`insert_toc`](crate::insert_toc) puts the table of contents, it's a comment so
+    /// it's invisible if the table of contents is never inserted
+    pub fn toc_placeholder(self) -> &'static str {
+        match self {
+            Flavor::Mdx => "{/* toc */}",
+            Flavor::Gfm | Flavor::CommonMark | Flavor::MdBook => "<!-- toc -->",
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use std::io;
+
+use crate::MarkdownWriter;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FrontMatterFormat {
+    /// Between `---` lines, read by most static site generators
+    #[default]
+    Yaml,
+    /// Between `+++` lines, e.g. for Hugo and Zola
+    Toml,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrontMatterValue {
+    String(String),
+    Integer(i64),
+    Bool(bool),
+    List(Vec<FrontMatterValue>),
+}
+
+impl From<&str> for FrontMatterValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for FrontMatterValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<i64> for FrontMatterValue {
+    fn from(value: i64) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<bool> for FrontMatterValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl<V: Into<FrontMatterValue>> From<Vec<V>> for FrontMatterValue {
+    fn from(values: Vec<V>) -> Self {
+        Self::List(values.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Metadata for static site generators, written in order at the top of the page
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrontMatter {
+    pub format: FrontMatterFormat,
+    pub entries: Vec<(String, FrontMatterValue)>,
+}
+
+impl FrontMatter {
+    pub fn new(format: FrontMatterFormat) -> Self {
+        Self {
+            format,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds `key`, or replaces its value in place if it was already added
+    pub fn entry(mut self, key: impl Into<String>, value: impl Into<FrontMatterValue>) -> Self {
+        let key = key.into();
+        let value = value.into();
+        match self
+            .entries
+            .iter_mut()
+            .find(|(existing, _)| *existing == key)
+        {
+            Some((_, existing)) => *existing = value,
+            None => self.entries.push((key, value)),
+        }
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&FrontMatterValue> {
+        self.entries
+            .iter()
+            .find(|(existing, _)| existing == key)
+            .map(|(_, value)| value)
+    }
+}
+
+impl<T> MarkdownWriter<T>
+where
+    T: io::Write,
+{
+    /// Writes the front matter, which has to be the first thing in the document. Strings are always
+    /// quoted so values like `yes`, `1.0` or `null` stay strings.
+    pub fn front_matter(&mut self, front_matter: &FrontMatter) -> io::Result<()> {
+        let (delimiter, separator) = match front_matter.format {
+            FrontMatterFormat::Yaml => ("---", ": "),
+            FrontMatterFormat::Toml => ("+++", " = "),
+        };
+        writeln!(self.writer, "{delimiter}")?;
+        for (key, value) in &front_matter.entries {
+            writeln!(
+                self.writer,
+                "{}{separator}{}",
+                format_key(key, front_matter.format),
+                format_value(value)
+            )?;
+        }
+        writeln!(self.writer, "{delimiter}")
+    }
+}
+
+fn format_key(key: &str, format: FrontMatterFormat) -> String {
+    let bare = match format {
+        // Plain YAML keys are parsed like values, so numbers and keywords like `yes` or `null`
+        // wouldn't be strings
+        FrontMatterFormat::Yaml => {
+            key.starts_with(|c: char| c.is_ascii_alphabetic())
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+                && !YAML_KEYWORDS.contains(&key.to_ascii_lowercase().as_str())
+        }
+        // TOML bare keys are always strings
+        FrontMatterFormat::Toml => {
+            !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+        }
+    };
+    if bare { key.to_string() } else { quote(key) }
+}
+
+// Booleans and null in YAML 1.1, which is still what many static site generators parse
+const YAML_KEYWORDS: &[&str] = &["true", "false", "yes", "no", "on", "off", "y", "n", "null"];
+
+// Flow style lists and double quoted strings are written the same way in YAML and TOML
+fn format_value(value: &FrontMatterValue) -> String {
+    match value {
+        FrontMatterValue::String(value) => quote(value),
+        FrontMatterValue::Integer(value) => value.to_string(),
+        FrontMatterValue::Bool(value) => value.to_string(),
+        FrontMatterValue::List(values) => {
+            let values: Vec<_> = values.iter().map(format_value).collect();
+            format!("[{}]", values.join(", "))
+        }
+    }
+}
+
+// These escapes mean the same in YAML double quoted strings and TOML basic strings
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            _ if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FrontMatter, FrontMatterFormat};
+    use crate::MarkdownWriter;
+
+    fn write(front_matter: &FrontMatter) -> String {
+        let mut writer = MarkdownWriter::new(Vec::new());
+        writer.front_matter(front_matter).unwrap();
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    fn keys(format: FrontMatterFormat) -> FrontMatter {
+        [
+            "title",
+            "sidebar_position-2",
+            "true",
+            "Null",
+            "yes",
+            "n",
+            "1",
+            "2nd",
+            "_a",
+            "a b",
+            "",
+        ]
+        .into_iter()
+        .fold(FrontMatter::new(format), |front_matter, key| {
+            front_matter.entry(key, 1)
+        })
+    }
+
+    #[test]
+    fn quotes_yaml_keys() {
+        assert_eq!(
+            write(&keys(FrontMatterFormat::Yaml)),
+            "---\ntitle: 1\nsidebar_position-2: 1\n\"true\": 1\n\"Null\": 1\n\"yes\": 1\n\"n\": \
+             1\n\"1\": 1\n\"2nd\": 1\n\"_a\": 1\n\"a b\": 1\n\"\": 1\n---\n"
+        );
+    }
+
+    #[test]
+    fn quotes_toml_keys() {
+        assert_eq!(
+            write(&keys(FrontMatterFormat::Toml)),
+            "+++\ntitle = 1\nsidebar_position-2 = 1\ntrue = 1\nNull = 1\nyes = 1\nn = 1\n1 = \
+             1\n2nd = 1\n_a = 1\n\"a b\" = 1\n\"\" = 1\n+++\n"
+        );
+    }
+
+    #[test]
+    fn quotes_values() {
+        let front_matter = FrontMatter::new(FrontMatterFormat::Yaml)
+            .entry("title", "a \"b\"\n\\c")
+            .entry("draft", false)
+            .entry("tags", vec!["yes", "null"]);
+        assert_eq!(
+            write(&front_matter),
+            "---\ntitle: \"a \\\"b\\\"\\n\\\\c\"\ndraft: false\ntags: [\"yes\", \"null\"]\n---\n"
+        );
+    }
+}
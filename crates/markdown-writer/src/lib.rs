@@ -1,16 +1,35 @@
-use std::io;
-use std::sync::LazyLock;
+mod admonition;
+mod code_block;
+mod escape;
+mod flavor;
+mod front_matter;
+mod list;
+mod slug;
+mod table;
+mod toc;
 
-use linkify::LinkFinder;
-use regex::Regex;
+use std::io;
 
-static SPECIAL_CHAR_RE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(?<x>[\\\\`*_{}\\[\\]()<>#+\-!~])").unwrap());
+pub use admonition::AdmonitionKind;
+pub use code_block::InfoString;
+use escape::escape_link_text;
+pub use escape::{escape_destination, escape_text, escape_title};
+pub use flavor::Flavor;
+pub use front_matter::{FrontMatter, FrontMatterFormat, FrontMatterValue};
+pub use list::{List, ListBlock, ListItem, ListKind};
+pub use slug::{Slugger, slug};
+pub use table::{Alignment, Column};
+pub use toc::{Heading, TocOptions, insert_toc};
 
 pub struct MarkdownWriter<T> {
     writer: T,
+    flavor: Flavor,
+    /// Every header is registered so anchors stay unique, even if they're not requested
+    slugger: Slugger,
+    headings: Vec<Heading>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum HeaderLevel {
     One = 1,
     Two = 2,
@@ -20,12 +39,42 @@ pub enum HeaderLevel {
     Six = 6,
 }
 
+impl HeaderLevel {
+    /// The level below this one, six is the lowest level so it stays the same
+    pub fn nested(self) -> Self {
+        match self {
+            HeaderLevel::One => HeaderLevel::Two,
+            HeaderLevel::Two => HeaderLevel::Three,
+            HeaderLevel::Three => HeaderLevel::Four,
+            HeaderLevel::Four => HeaderLevel::Five,
+            HeaderLevel::Five | HeaderLevel::Six => HeaderLevel::Six,
+        }
+    }
+}
+
 impl<T> MarkdownWriter<T>
 where
     T: io::Write,
 {
     pub fn new(writer: T) -> Self {
-        Self { writer }
+        Self::with_flavor(writer, Flavor::default())
+    }
+
+    pub fn with_flavor(writer: T, flavor: Flavor) -> Self {
+        Self {
+            writer,
+            flavor,
+            slugger: Slugger::new(),
+            headings: Vec::new(),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.writer
+    }
+
+    pub fn flavor(&self) -> Flavor {
+        self.flavor
     }
 
     pub fn bold(&mut self, text: &str) -> io::Result<()> {
@@ -35,22 +84,69 @@ where
         write!(self.writer, "**{text}**")
     }
 
+    /// Writes `code` as an inline code span, see [`code_span`]
+    pub fn inline_code(&mut self, code: &str) -> io::Result<()> {
+        if code.is_empty() {
+            return Ok(());
+        }
+        write!(self.writer, "{}", code_span(code))
+    }
+
     pub fn header(&mut self, level: HeaderLevel, text: &str) -> io::Result<()> {
+        self.header_anchor(level, text)?;
+        Ok(())
+    }
+
+    /// Writes a header and returns the anchor renderers like GitHub generate for it, which can be
+    /// linked to with `#anchor`
+    pub fn header_anchor(&mut self, level: HeaderLevel, text: &str) -> io::Result<String> {
         let header_marker = "#".repeat(level as usize);
-        write!(self.writer, "{header_marker} {text}")
+        write!(self.writer, "{header_marker} {text}")?;
+        let anchor = self.slugger.slug(text);
+        self.push_heading(level, text, &anchor);
+        Ok(anchor)
+    }
+
+    fn push_heading(&mut self, level: HeaderLevel, text: &str, anchor: &str) {
+        self.headings.push(Heading {
+            level,
+            text: text.to_string(),
+            anchor: anchor.to_string(),
+        });
+    }
+
+    /// Writes `text` so it's rendered literally, see [`escape_text`]
+    pub fn text(&mut self, text: &str) -> io::Result<()> {
+        write!(self.writer, "{}", escape_text(text, self.flavor))
     }
 
+    /// Writes a link, or only the text if `href` is empty
     pub fn link(&mut self, text: &str, href: &str) -> io::Result<()> {
+        self.write_link(text, href, None)
+    }
+
+    /// Like [`Self::link`], with a title that's usually shown when hovering over the link
+    pub fn link_with_title(&mut self, text: &str, href: &str, title: &str) -> io::Result<()> {
+        self.write_link(text, href, Some(title))
+    }
+
+    fn write_link(&mut self, text: &str, href: &str, title: Option<&str>) -> io::Result<()> {
         if text.is_empty() {
             return Ok(());
         }
         if href.is_empty() {
-            return self.write_escaped(text);
+            return self.text(text);
+        }
+        let text = escape_link_text(text);
+        let destination = escape_destination(href, self.flavor);
+        match title {
+            Some(title) => write!(
+                self.writer,
+                "[{text}]({destination} {})",
+                escape_title(title)
+            ),
+            None => write!(self.writer, "[{text}]({destination})"),
         }
-        write!(self.writer, "[")?;
-        self.write_escaped(text)?;
-        write!(self.writer, "]")?;
-        write!(self.writer, "{}", href)
     }
 
     pub fn legacy_code_block(&mut self, code: &str) -> io::Result<()> {
@@ -59,31 +155,45 @@ where
         write!(self.writer, "{}", code)
     }
 
+    /// Uses a fence that's longer than any run of backticks in `code`, so code containing fences
+    /// of its own is kept intact
     pub fn fenced_code_block(&mut self, code: &str, language: Option<&str>) -> io::Result<()> {
-        write!(
-            self.writer,
-            "```{}\n{code}\n```",
-            language.unwrap_or_default()
-        )
+        self.fenced_code_block_with_info(code, &InfoString::new(language))
     }
 
+    /// An empty element that can be linked to with `#anchor`. Only GitHub keeps `name`, which it
+    /// prefixes `id`s with `user-content-` otherwise.
     pub fn anchor(&mut self, anchor: &str) -> io::Result<()> {
+        let attribute = match self.flavor {
+            Flavor::Gfm => "name",
+            Flavor::CommonMark | Flavor::Mdx | Flavor::MdBook => "id",
+        };
         write!(
             self.writer,
-            "<a name=\"{}\"></a>",
+            "<a {attribute}=\"{}\"></a>",
             urlencoding::encode(anchor)
         )
     }
 
+    /// Writes a header that can also be linked to with `#anchor`. Flavors with header attributes
+    /// use `anchor` as the header's own anchor, so it's the one listed in the table of contents.
     pub fn anchor_header(
         &mut self,
         level: HeaderLevel,
         text: &str,
         anchor: &str,
     ) -> io::Result<()> {
-        self.anchor(anchor)?;
-        self.newline()?;
-        self.header(level, text)
+        if !self.flavor.supports_header_attributes() {
+            self.anchor(anchor)?;
+            self.newline()?;
+            return self.header(level, text);
+        }
+        let header_marker = "#".repeat(level as usize);
+        let anchor = urlencoding::encode(anchor);
+        write!(self.writer, "{header_marker} {text} {{#{anchor}}}")?;
+        self.slugger.reserve(&anchor);
+        self.push_heading(level, text, &anchor);
+        Ok(())
     }
 
     pub fn newline(&mut self) -> io::Result<()> {
@@ -94,23 +204,25 @@ where
         let n = "\n".repeat(count);
         write!(self.writer, "{n}")
     }
+}
 
-    fn write_escaped(&mut self, text: &str) -> io::Result<()> {
-        let link_finder = LinkFinder::new();
-        let mut pos = 0;
-        for link in link_finder.links(text) {
-            self.write_escaped_raw(&text[pos..link.start()])?;
-            write!(self.writer, "{}", &text[link.start()..link.end()])?;
-            pos = link.end();
-        }
-        if pos < text.len() {
-            write!(self.writer, "{}", &text[pos..])?;
-        }
-        Ok(())
-    }
+/// Wraps `code` in a fence of more backticks than the longest run of backticks inside it. Per
+/// CommonMark, a space is added inside the fence if `code` starts or ends with a backtick, or
+/// starts and ends with a space since renderers would strip one on each side otherwise.
+///
+/// Useful for building inline code that's passed to other methods, e.g. in a header or table
+/// cell.
+pub fn code_span(code: &str) -> String {
+    let fence = "`".repeat(code_block::longest_run(code, '`') + 1);
 
-    fn write_escaped_raw(&mut self, text: &str) -> io::Result<()> {
-        write!(self.writer, "{}", SPECIAL_CHAR_RE.replace_all(text, r"\$x"))
+    let only_spaces = code.chars().all(|c| c == ' ');
+    let pad = code.starts_with('`')
+        || code.ends_with('`')
+        || (code.starts_with(' ') && code.ends_with(' ') && !only_spaces);
+    if pad {
+        format!("{fence} {code} {fence}")
+    } else {
+        format!("{fence}{code}{fence}")
     }
 }
 
@@ -123,3 +235,132 @@ impl<W: io::Write> io::Write for MarkdownWriter<W> {
         self.writer.flush()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Column, Flavor, HeaderLevel, MarkdownWriter, TocOptions};
+
+    fn write(flavor: Flavor, f: impl FnOnce(&mut MarkdownWriter<Vec<u8>>)) -> String {
+        let mut writer = MarkdownWriter::with_flavor(Vec::new(), flavor);
+        f(&mut writer);
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn link() {
+        let link = |text, href| write(Flavor::Gfm, |w| w.link(text, href).unwrap());
+        assert_eq!(link("a", "b.md"), "[a](b.md)");
+        assert_eq!(link("[a]*b*", "c d"), r"[\[a\]\*b\*](<c d>)");
+        assert_eq!(link("a", ""), "a");
+        assert_eq!(link("", "b.md"), "");
+    }
+
+    #[test]
+    fn link_text_with_url() {
+        // URLs aren't linked inside links, so they're escaped even where they would be elsewhere
+        let text = "see https://x.com/*a*_b_, *then*";
+        for flavor in [Flavor::Gfm, Flavor::Mdx, Flavor::CommonMark] {
+            assert_eq!(
+                write(flavor, |w| w.link(text, "a.md").unwrap()),
+                r"[see https://x.com/\*a\*\_b\_, \*then\*](a.md)"
+            );
+        }
+    }
+
+    #[test]
+    fn link_with_title() {
+        assert_eq!(
+            write(Flavor::Gfm, |w| w
+                .link_with_title("a", r"b\c", r#"say "hi""#)
+                .unwrap()),
+            r#"[a](b\\c "say \"hi\"")"#
+        );
+    }
+
+    #[test]
+    fn anchor_per_flavor() {
+        assert_eq!(
+            write(Flavor::Gfm, |w| w.anchor("a b").unwrap()),
+            r#"<a name="a%20b"></a>"#
+        );
+        assert_eq!(
+            write(Flavor::Mdx, |w| w.anchor("a b").unwrap()),
+            r#"<a id="a%20b"></a>"#
+        );
+    }
+
+    #[test]
+    fn anchor_header_per_flavor() {
+        let headers = |flavor| {
+            let mut writer = MarkdownWriter::with_flavor(Vec::new(), flavor);
+            writer
+                .anchor_header(HeaderLevel::Two, "Config", "config")
+                .unwrap();
+            writer.newline().unwrap();
+            // The custom anchor is taken, so this one gets a suffix
+            let anchor = writer.header_anchor(HeaderLevel::Two, "Config").unwrap();
+            let anchors: Vec<_> = writer.headings().iter().map(|h| h.anchor.clone()).collect();
+            (
+                String::from_utf8(writer.into_inner()).unwrap(),
+                anchor,
+                anchors,
+            )
+        };
+
+        let (output, anchor, anchors) = headers(Flavor::MdBook);
+        assert_eq!(output, "## Config {#config}\n## Config");
+        assert_eq!(anchor, "config-1");
+        assert_eq!(anchors, ["config", "config-1"]);
+
+        let (output, anchor, anchors) = headers(Flavor::Gfm);
+        assert_eq!(output, "<a name=\"config\"></a>\n## Config\n## Config");
+        assert_eq!(anchor, "config-1");
+        assert_eq!(anchors, ["config", "config-1"]);
+    }
+
+    #[test]
+    fn table_line_breaks_per_flavor() {
+        let table = |flavor| {
+            write(flavor, |w| {
+                w.table(&[Column::new("a")], &[vec!["b\nc|d"]], false)
+                    .unwrap()
+            })
+        };
+        assert_eq!(table(Flavor::Gfm), "| a |\n| --- |\n| b<br>c\\|d |\n");
+        assert_eq!(table(Flavor::Mdx), "| a |\n| --- |\n| b<br />c\\|d |\n");
+        assert_eq!(
+            table(Flavor::CommonMark).lines().collect::<Vec<_>>(),
+            [
+                "<table>",
+                "<thead>",
+                "<tr><th>a</th></tr>",
+                "</thead>",
+                "<tbody>",
+                "<tr><td>b<br>c|d</td></tr>",
+                "</tbody>",
+                "</table>",
+            ]
+        );
+    }
+
+    #[test]
+    fn toc_placeholder_per_flavor() {
+        let document = |flavor| {
+            write(flavor, |w| {
+                w.toc_placeholder().unwrap();
+                w.newline().unwrap();
+                w.header(HeaderLevel::Two, "A").unwrap();
+            })
+        };
+        for flavor in [Flavor::Gfm, Flavor::Mdx] {
+            let mut writer = MarkdownWriter::with_flavor(Vec::new(), flavor);
+            writer.header(HeaderLevel::Two, "A").unwrap();
+            let toc = writer.toc(&TocOptions::default());
+            assert_eq!(
+                crate::insert_toc(&document(flavor), &toc, flavor),
+                "- [A](#a)\n## A"
+            );
+        }
+        assert!(document(Flavor::Mdx).starts_with("{/* toc */}"));
+    }
+}
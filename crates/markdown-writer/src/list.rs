@@ -0,0 +1,240 @@
+use std::io::{self, Write};
+
+use crate::{Flavor, MarkdownWriter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListKind {
+    Bullet,
+    /// Numbered from `start`
+    Ordered {
+        start: u64,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct List {
+    pub kind: ListKind,
+    pub items: Vec<ListItem>,
+}
+
+impl List {
+    pub fn bullet() -> Self {
+        Self {
+            kind: ListKind::Bullet,
+            items: Vec::new(),
+        }
+    }
+
+    pub fn ordered(start: u64) -> Self {
+        Self {
+            kind: ListKind::Ordered { start },
+            items: Vec::new(),
+        }
+    }
+
+    pub fn item(mut self, item: impl Into<ListItem>) -> Self {
+        self.items.push(item.into());
+        self
+    }
+}
+
+/// The text of the item and any blocks that follow it, which are indented to stay inside the
+/// item. Text isn't escaped so it can contain inline markdown.
+#[derive(Debug, Clone)]
+pub struct ListItem {
+    pub text: String,
+    /// Renders a task list checkbox, checked if `true`
+    pub task: Option<bool>,
+    pub blocks: Vec<ListBlock>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ListBlock {
+    Paragraph(String),
+    CodeBlock {
+        code: String,
+        language: Option<String>,
+    },
+    List(List),
+}
+
+impl ListItem {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            task: None,
+            blocks: Vec::new(),
+        }
+    }
+
+    pub fn task(mut self, checked: bool) -> Self {
+        self.task = Some(checked);
+        self
+    }
+
+    pub fn paragraph(mut self, text: impl Into<String>) -> Self {
+        self.blocks.push(ListBlock::Paragraph(text.into()));
+        self
+    }
+
+    pub fn code_block(mut self, code: impl Into<String>, language: Option<&str>) -> Self {
+        self.blocks.push(ListBlock::CodeBlock {
+            code: code.into(),
+            language: language.map(str::to_string),
+        });
+        self
+    }
+
+    pub fn list(mut self, list: List) -> Self {
+        self.blocks.push(ListBlock::List(list));
+        self
+    }
+}
+
+impl From<&str> for ListItem {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<String> for ListItem {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+impl<T> MarkdownWriter<T>
+where
+    T: io::Write,
+{
+    /// Writes the list with a line break after every item
+    pub fn list(&mut self, list: &List) -> io::Result<()> {
+        for (i, item) in list.items.iter().enumerate() {
+            let marker = match list.kind {
+                ListKind::Bullet => "- ".to_string(),
+                ListKind::Ordered { start } => format!("{}. ", start + i as u64),
+            };
+            let content = item_content(item, self.flavor)?;
+            // CommonMark nests everything indented to the start of the item's text
+            let indent = " ".repeat(marker.len());
+            write!(self.writer, "{marker}")?;
+            for (i, line) in content.lines().enumerate() {
+                if i > 0 && !line.is_empty() {
+                    write!(self.writer, "{indent}")?;
+                }
+                writeln!(self.writer, "{line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn item_content(item: &ListItem, flavor: Flavor) -> io::Result<String> {
+    let mut writer = MarkdownWriter::with_flavor(Vec::new(), flavor);
+    match item.task {
+        Some(true) => write!(writer, "[x] ")?,
+        Some(false) => write!(writer, "[ ] ")?,
+        None => {}
+    }
+    writeln!(writer, "{}", item.text)?;
+    for block in &item.blocks {
+        match block {
+            // Without a blank line the paragraph would continue the previous one
+            ListBlock::Paragraph(text) => writeln!(writer, "\n{text}")?,
+            ListBlock::CodeBlock { code, language } => {
+                writer.fenced_code_block(code, language.as_deref())?;
+                writer.newline()?;
+            }
+            ListBlock::List(list) => writer.list(list)?,
+        }
+    }
+    Ok(String::from_utf8(writer.writer).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{List, ListItem};
+    use crate::MarkdownWriter;
+
+    fn write(list: &List) -> Vec<String> {
+        let mut writer = MarkdownWriter::new(Vec::new());
+        writer.list(list).unwrap();
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        output.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn nested_lists_are_indented_to_the_text() {
+        let nested = List::bullet()
+            .item("a")
+            .item(ListItem::new("b").list(List::ordered(1).item("c")));
+        assert_eq!(
+            write(&List::bullet().item(ListItem::new("x").list(nested.clone()))),
+            ["- x", "  - a", "  - b", "    1. c"]
+        );
+        // The marker gets wider once the numbers have two digits
+        assert_eq!(
+            write(
+                &List::ordered(9)
+                    .item(ListItem::new("nine").list(nested.clone()))
+                    .item(ListItem::new("ten").list(nested))
+            ),
+            [
+                "9. nine",
+                "   - a",
+                "   - b",
+                "     1. c",
+                "10. ten",
+                "    - a",
+                "    - b",
+                "      1. c",
+            ]
+        );
+    }
+
+    #[test]
+    fn code_block_in_item() {
+        let list = List::ordered(1).item(
+            ListItem::new("Run:")
+                .code_block("cargo build\n\ncargo test", Some("sh"))
+                .paragraph("Then publish."),
+        );
+        assert_eq!(
+            write(&list),
+            [
+                "1. Run:",
+                "   ```sh",
+                "   cargo build",
+                // Blank lines don't end the item, so they aren't indented
+                "",
+                "   cargo test",
+                "   ```",
+                "",
+                "   Then publish.",
+            ]
+        );
+    }
+
+    #[test]
+    fn tight_and_loose_items() {
+        assert_eq!(write(&List::bullet().item("a").item("b")), ["- a", "- b"]);
+        // A paragraph is separated by a blank line, which makes the list loose
+        assert_eq!(
+            write(
+                &List::bullet()
+                    .item(ListItem::new("a").paragraph("more"))
+                    .item("b")
+            ),
+            ["- a", "", "  more", "- b"]
+        );
+    }
+
+    #[test]
+    fn task_items() {
+        let list = List::bullet()
+            .item(ListItem::new("done").task(true))
+            .item(ListItem::new("todo").task(false))
+            .item("plain");
+        assert_eq!(write(&list), ["- [x] done", "- [ ] todo", "- plain"]);
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use unicode_normalization::char::is_combining_mark;
+
+/// The anchor GitHub generates for a heading: lowercased, with punctuation and symbols removed and
+/// spaces replaced by `-`. Letters, numbers, combining marks like the accent of a decomposed `é`,
+/// `-` and `_` are kept. mdBook and most other renderers follow the same scheme.
+///
+/// The text is treated as markdown, so emphasis markers, code span backticks and backslash escapes
+/// are dropped since they aren't part of the rendered heading. `_` is only kept inside a word, as
+/// in `snake_case`, or when it's escaped. The destination of a link would be kept though.
+pub fn slug(text: &str) -> String {
+    let chars: Vec<char> = text.trim().to_lowercase().chars().collect();
+    let is_word = |c: Option<&char>| c.is_some_and(|&c| is_kept(c));
+    let mut slug = String::with_capacity(text.len());
+    let mut i = 0;
+    while let Some(&c) = chars.get(i) {
+        match c {
+            // The escaped character is part of the text
+            '\\' if chars.get(i + 1).is_some_and(char::is_ascii_punctuation) => {
+                if matches!(chars[i + 1], '-' | '_') {
+                    slug.push(chars[i + 1]);
+                }
+                i += 2;
+                continue;
+            }
+            // A run of underscores only marks emphasis at the start or end of a word
+            '_' => {
+                let end = i + chars[i..].iter().take_while(|&&c| c == '_').count();
+                if i > 0 && is_word(chars.get(i - 1)) && is_word(chars.get(end)) {
+                    slug.extend(&chars[i..end]);
+                }
+                i = end;
+                continue;
+            }
+            ' ' => slug.push('-'),
+            '-' => slug.push(c),
+            _ if is_kept(c) => slug.push(c),
+            _ => {}
+        }
+        i += 1;
+    }
+    slug
+}
+
+fn is_kept(c: char) -> bool {
+    c.is_alphanumeric() || is_combining_mark(c)
+}
+
+/// Generates unique heading anchors for a page. Repeated headings get `-1`, `-2`, etc. appended,
+/// skipping any suffix that's already taken by another heading.
+#[derive(Debug, Clone, Default)]
+pub struct Slugger {
+    occurrences: HashMap<String, usize>,
+}
+
+impl Slugger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn slug(&mut self, text: &str) -> String {
+        let original = slug(text);
+        let mut slug = original.clone();
+        while self.occurrences.contains_key(&slug) {
+            let count = self.occurrences.get_mut(&original).unwrap();
+            *count += 1;
+            slug = format!("{original}-{count}");
+        }
+        self.occurrences.insert(slug.clone(), 0);
+        slug
+    }
+
+    /// Marks `anchor` as taken, e.g. when it's set explicitly, so it isn't generated for another
+    /// heading
+    pub fn reserve(&mut self, anchor: &str) {
+        self.occurrences.entry(anchor.to_string()).or_insert(0);
+    }
+
+    /// Forgets every generated anchor, e.g. when starting a new page
+    pub fn reset(&mut self) {
+        self.occurrences.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Slugger, slug};
+
+    #[test]
+    fn strips_punctuation() {
+        assert_eq!(slug("Hello, World!"), "hello-world");
+        assert_eq!(slug("  `code` & *emphasis*  "), "code--emphasis");
+        assert_eq!(slug("a-b c"), "a-b-c");
+        assert_eq!(slug("Option<T>::map()"), "optiontmap");
+    }
+
+    #[test]
+    fn keeps_underscores_inside_words() {
+        assert_eq!(slug("snake_case and __dunder__"), "snake_case-and-dunder");
+        assert_eq!(slug("_foo_"), "foo");
+        assert_eq!(slug("a__b"), "a__b");
+        assert_eq!(slug(r"\_foo\_"), "_foo_");
+    }
+
+    #[test]
+    fn keeps_unicode_letters_and_marks() {
+        assert_eq!(slug("Ünïcödé Ⅻ 日本"), "ünïcödé-ⅻ-日本");
+        // Decomposed, with U+0301 COMBINING ACUTE ACCENT
+        assert_eq!(slug("Cafe\u{301}"), "cafe\u{301}");
+        assert_eq!(slug("😀 emoji"), "-emoji");
+    }
+
+    #[test]
+    fn dedups_suffixes() {
+        let mut slugger = Slugger::new();
+        assert_eq!(slugger.slug("a"), "a");
+        assert_eq!(slugger.slug("a"), "a-1");
+        // Taken by the second `a`
+        assert_eq!(slugger.slug("a-1"), "a-1-1");
+        assert_eq!(slugger.slug("a"), "a-2");
+
+        let mut slugger = Slugger::new();
+        assert_eq!(slugger.slug("a-1"), "a-1");
+        assert_eq!(slugger.slug("a"), "a");
+        // Skips the suffix of the literal `a-1` heading
+        assert_eq!(slugger.slug("a"), "a-2");
+
+        slugger.reset();
+        assert_eq!(slugger.slug("a"), "a");
+    }
+
+    #[test]
+    fn reserved_anchors_are_skipped() {
+        let mut slugger = Slugger::new();
+        slugger.reserve("a");
+        assert_eq!(slugger.slug("A"), "a-1");
+    }
+}
@@ -0,0 +1,295 @@
+use std::io;
+
+use crate::{Flavor, MarkdownWriter};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Alignment {
+    /// Left to the renderer, which usually means left aligned
+    #[default]
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub header: String,
+    pub alignment: Alignment,
+    /// Cells are padded with spaces to this many characters so the source lines up, longer cells
+    /// are written as is
+    pub width: usize,
+}
+
+impl Column {
+    pub fn new(header: impl Into<String>) -> Self {
+        Self {
+            header: header.into(),
+            alignment: Alignment::None,
+            width: 0,
+        }
+    }
+
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+}
+
+impl<T> MarkdownWriter<T>
+where
+    T: io::Write,
+{
+    /// Writes a GFM table with all rows at once. With `pad`, every column is padded to its widest
+    /// cell.
+    ///
+    /// Cells aren't escaped besides `|` and line breaks, so they can contain inline markdown like
+    /// links and code. Flavors without tables get an HTML table instead, where markdown isn't
+    /// rendered so cells are HTML-escaped.
+    pub fn table<R: AsRef<str>>(
+        &mut self,
+        columns: &[Column],
+        rows: &[Vec<R>],
+        pad: bool,
+    ) -> io::Result<()> {
+        let mut columns = columns.to_vec();
+        if pad {
+            for (i, column) in columns.iter_mut().enumerate() {
+                let widest = rows
+                    .iter()
+                    .filter_map(|row| row.get(i))
+                    .map(|cell| escape_cell(cell.as_ref(), self.flavor).chars().count())
+                    .chain([escape_cell(&column.header, self.flavor).chars().count()])
+                    .max()
+                    .unwrap_or_default();
+                // The delimiter row is never narrower than three characters
+                column.width = column.width.max(widest).max(3);
+            }
+        }
+        self.table_header(&columns)?;
+        for row in rows {
+            self.table_row(&columns, row)?;
+        }
+        self.table_end()
+    }
+
+    /// Starts a table that's written row by row with [`Self::table_row`], so it doesn't have to be
+    /// buffered. Columns are only padded to their configured width. The table has to be finished
+    /// with [`Self::table_end`].
+    pub fn table_header(&mut self, columns: &[Column]) -> io::Result<()> {
+        if !self.flavor.supports_tables() {
+            writeln!(self.writer, "<table>\n<thead>")?;
+            let headers: Vec<_> = columns.iter().map(|column| &column.header).collect();
+            self.write_html_row("th", columns, &headers)?;
+            return writeln!(self.writer, "</thead>\n<tbody>");
+        }
+        let headers: Vec<_> = columns.iter().map(|column| &column.header).collect();
+        self.table_row(columns, &headers)?;
+
+        let delimiters: Vec<_> = columns
+            .iter()
+            .map(|column| {
+                // At least three characters so the delimiter row is recognized by every renderer
+                let width = column.width.max(3);
+                match column.alignment {
+                    Alignment::None => "-".repeat(width),
+                    Alignment::Left => format!(":{}", "-".repeat(width - 1)),
+                    Alignment::Center => format!(":{}:", "-".repeat(width - 2)),
+                    Alignment::Right => format!("{}:", "-".repeat(width - 1)),
+                }
+            })
+            .collect();
+        self.write_table_cells(&delimiters)
+    }
+
+    /// Writes one row of a table started with [`Self::table_header`]. Missing cells are left empty
+    /// and extra cells are dropped.
+    pub fn table_row<R: AsRef<str>>(&mut self, columns: &[Column], cells: &[R]) -> io::Result<()> {
+        if !self.flavor.supports_tables() {
+            return self.write_html_row("td", columns, cells);
+        }
+        let cells: Vec<_> = columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let cell = cells
+                    .get(i)
+                    .map(|cell| escape_cell(cell.as_ref(), self.flavor));
+                pad_cell(&cell.unwrap_or_default(), column)
+            })
+            .collect();
+        self.write_table_cells(&cells)
+    }
+
+    /// Ends a table started with [`Self::table_header`]
+    pub fn table_end(&mut self) -> io::Result<()> {
+        if !self.flavor.supports_tables() {
+            writeln!(self.writer, "</tbody>\n</table>")?;
+        }
+        Ok(())
+    }
+
+    fn write_table_cells(&mut self, cells: &[String]) -> io::Result<()> {
+        writeln!(self.writer, "| {} |", cells.join(" | "))
+    }
+
+    fn write_html_row<R: AsRef<str>>(
+        &mut self,
+        tag: &str,
+        columns: &[Column],
+        cells: &[R],
+    ) -> io::Result<()> {
+        write!(self.writer, "<tr>")?;
+        for (i, column) in columns.iter().enumerate() {
+            let cell = cells.get(i).map_or("", |cell| cell.as_ref());
+            let align = match column.alignment {
+                Alignment::None => "",
+                Alignment::Left => " align=\"left\"",
+                Alignment::Center => " align=\"center\"",
+                Alignment::Right => " align=\"right\"",
+            };
+            write!(
+                self.writer,
+                "<{tag}{align}>{}</{tag}>",
+                escape_html(cell, self.flavor)
+            )?;
+        }
+        writeln!(self.writer, "</tr>")
+    }
+}
+
+// A `|` would end the cell and a line break would end the table
+fn escape_cell(cell: &str, flavor: Flavor) -> String {
+    cell.replace('|', "\\|")
+        .replace("\r\n", flavor.line_break())
+        .replace(['\n', '\r'], flavor.line_break())
+}
+
+// A blank line would end the HTML block, so line breaks become `<br>` as well
+fn escape_html(cell: &str, flavor: Flavor) -> String {
+    cell.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace("\r\n", flavor.line_break())
+        .replace(['\n', '\r'], flavor.line_break())
+}
+
+fn pad_cell(cell: &str, column: &Column) -> String {
+    let padding = column.width.saturating_sub(cell.chars().count());
+    match column.alignment {
+        Alignment::None | Alignment::Left => format!("{cell}{}", " ".repeat(padding)),
+        Alignment::Center => {
+            let left = padding / 2;
+            format!("{}{cell}{}", " ".repeat(left), " ".repeat(padding - left))
+        }
+        Alignment::Right => format!("{}{cell}", " ".repeat(padding)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Alignment, Column};
+    use crate::MarkdownWriter;
+
+    fn write(columns: &[Column], rows: &[Vec<&str>], pad: bool) -> Vec<String> {
+        let mut writer = MarkdownWriter::new(Vec::new());
+        writer.table(columns, rows, pad).unwrap();
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        output.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn pads_to_widest_cell() {
+        let columns = [Column::new("Name"), Column::new("N")];
+        let rows = [vec!["a", "1"], vec!["longer", "22"]];
+        assert_eq!(
+            write(&columns, &rows, true),
+            [
+                "| Name   | N   |",
+                "| ------ | --- |",
+                "| a      | 1   |",
+                "| longer | 22  |",
+            ]
+        );
+        assert_eq!(
+            write(&columns, &rows, false),
+            [
+                "| Name | N |",
+                "| --- | --- |",
+                "| a | 1 |",
+                "| longer | 22 |"
+            ]
+        );
+    }
+
+    #[test]
+    fn configured_width() {
+        let columns = [Column::new("a").width(5)];
+        // Longer cells are written as is
+        assert_eq!(
+            write(&columns, &[vec!["b"], vec!["longer"]], false),
+            ["| a     |", "| ----- |", "| b     |", "| longer |"]
+        );
+        // Padding only ever widens the column
+        assert_eq!(
+            write(&columns, &[vec!["b"]], true),
+            ["| a     |", "| ----- |", "| b     |"]
+        );
+    }
+
+    #[test]
+    fn alignments() {
+        let columns = [
+            Column::new("n").alignment(Alignment::None).width(5),
+            Column::new("l").alignment(Alignment::Left).width(5),
+            Column::new("c").alignment(Alignment::Center).width(5),
+            Column::new("r").alignment(Alignment::Right).width(5),
+        ];
+        assert_eq!(
+            write(&columns, &[vec!["ab", "ab", "ab", "ab"]], false),
+            [
+                "| n     | l     |   c   |     r |",
+                "| ----- | :---- | :---: | ----: |",
+                "| ab    | ab    |  ab   |    ab |",
+            ]
+        );
+        // Delimiters keep three characters without a width
+        let columns = [
+            Column::new("l").alignment(Alignment::Left),
+            Column::new("c").alignment(Alignment::Center),
+            Column::new("r").alignment(Alignment::Right),
+        ];
+        assert_eq!(write(&columns, &[], false)[1], "| :-- | :-: | --: |");
+    }
+
+    #[test]
+    fn escaped_pipes_count_toward_width() {
+        let columns = [Column::new("a")];
+        assert_eq!(
+            write(&columns, &[vec!["b|c"], vec!["d"]], true),
+            ["| a    |", "| ---- |", r"| b\|c |", "| d    |"]
+        );
+    }
+
+    #[test]
+    fn row_by_row() {
+        let columns = [Column::new("a").width(3), Column::new("b")];
+        let mut writer = MarkdownWriter::new(Vec::new());
+        writer.table_header(&columns).unwrap();
+        // Missing cells are empty and extra ones dropped
+        writer.table_row(&columns, &["1"]).unwrap();
+        writer.table_row(&columns, &["1", "2", "3"]).unwrap();
+        writer.table_end().unwrap();
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(
+            output.lines().collect::<Vec<_>>(),
+            ["| a   | b |", "| --- | --- |", "| 1   |  |", "| 1   | 2 |"]
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use std::io;
+
+use crate::{Flavor, HeaderLevel, List, ListItem, MarkdownWriter, escape_destination};
+
+/// A header written by a [`MarkdownWriter`]
+#[derive(Debug, Clone)]
+pub struct Heading {
+    pub level: HeaderLevel,
+    pub text: String,
+    pub anchor: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TocOptions {
+    pub min_level: HeaderLevel,
+    pub max_level: HeaderLevel,
+}
+
+impl Default for TocOptions {
+    /// Leaves out the page title and anything below the third level
+    fn default() -> Self {
+        Self {
+            min_level: HeaderLevel::Two,
+            max_level: HeaderLevel::Three,
+        }
+    }
+}
+
+impl TocOptions {
+    pub fn min_level(mut self, min_level: HeaderLevel) -> Self {
+        self.min_level = min_level;
+        self
+    }
+
+    pub fn max_level(mut self, max_level: HeaderLevel) -> Self {
+        self.max_level = max_level;
+        self
+    }
+}
+
+impl<T> MarkdownWriter<T>
+where
+    T: io::Write,
+{
+    /// See [`Flavor::toc_placeholder`]
+    pub fn toc_placeholder(&mut self) -> io::Result<()> {
+        write!(self.writer, "{}", self.flavor.toc_placeholder())
+    }
+
+    /// Every header written so far, in order
+    pub fn headings(&self) -> &[Heading] {
+        &self.headings
+    }
+
+    /// A nested list linking to the headers written so far
+    pub fn toc(&self, options: &TocOptions) -> List {
+        let headings: Vec<_> = self
+            .headings
+            .iter()
+            .filter(|heading| (options.min_level..=options.max_level).contains(&heading.level))
+            .collect();
+        toc_list(&headings, self.flavor)
+    }
+}
+
+// Headers below a header are nested in its item until one that's at the same level or above
+fn toc_list(headings: &[&Heading], flavor: Flavor) -> List {
+    let mut list = List::bullet();
+    let mut i = 0;
+    while let Some(heading) = headings.get(i) {
+        let end = headings[i + 1..]
+            .iter()
+            .position(|next| next.level <= heading.level)
+            .map_or(headings.len(), |position| i + 1 + position);
+        let destination = escape_destination(&format!("#{}", heading.anchor), flavor);
+        let mut item = ListItem::new(format!("[{}]({destination})", heading.text));
+        if end > i + 1 {
+            item = item.list(toc_list(&headings[i + 1..end], flavor));
+        }
+        list = list.item(item);
+        i = end;
+    }
+    list
+}
+
+/// Replaces the first placeholder of `flavor` in `document` with `toc`
+pub fn insert_toc(document: &str, toc: &List, flavor: Flavor) -> String {
+    let mut writer = MarkdownWriter::with_flavor(Vec::new(), flavor);
+    writer.list(toc).unwrap();
+    let toc = String::from_utf8(writer.writer).unwrap();
+    // The list ends with a line break, the placeholder doesn't
+    document.replacen(flavor.toc_placeholder(), toc.trim_end(), 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TocOptions;
+    use crate::{HeaderLevel, MarkdownWriter};
+
+    fn toc(headers: &[(HeaderLevel, &str)], options: TocOptions) -> Vec<String> {
+        let mut writer = MarkdownWriter::new(Vec::new());
+        for (level, text) in headers {
+            writer.header(*level, text).unwrap();
+        }
+        let mut list = MarkdownWriter::new(Vec::new());
+        list.list(&writer.toc(&options)).unwrap();
+        let output = String::from_utf8(list.into_inner()).unwrap();
+        output.lines().map(str::to_string).collect()
+    }
+
+    const HEADERS: [(HeaderLevel, &str); 7] = [
+        (HeaderLevel::One, "Title"),
+        (HeaderLevel::Two, "A"),
+        (HeaderLevel::Three, "B"),
+        (HeaderLevel::Four, "C"),
+        (HeaderLevel::Three, "D"),
+        (HeaderLevel::Two, "E"),
+        // Skips a level
+        (HeaderLevel::Four, "F"),
+    ];
+
+    #[test]
+    fn nests_headers_below_their_parent() {
+        let options = TocOptions::default().max_level(HeaderLevel::Four);
+        assert_eq!(
+            toc(&HEADERS, options),
+            [
+                "- [A](#a)",
+                "  - [B](#b)",
+                "    - [C](#c)",
+                "  - [D](#d)",
+                "- [E](#e)",
+                "  - [F](#f)",
+            ]
+        );
+    }
+
+    #[test]
+    fn deeper_first_header() {
+        let headers = [(HeaderLevel::Three, "A"), (HeaderLevel::Two, "B")];
+        assert_eq!(
+            toc(&headers, TocOptions::default()),
+            ["- [A](#a)", "- [B](#b)"]
+        );
+    }
+
+    #[test]
+    fn filters_levels() {
+        // The title and fourth level are left out by default
+        assert_eq!(
+            toc(&HEADERS, TocOptions::default()),
+            ["- [A](#a)", "  - [B](#b)", "  - [D](#d)", "- [E](#e)"]
+        );
+        assert_eq!(
+            toc(
+                &HEADERS,
+                TocOptions::default()
+                    .min_level(HeaderLevel::One)
+                    .max_level(HeaderLevel::Two)
+            ),
+            ["- [Title](#title)", "  - [A](#a)", "  - [E](#e)"]
+        );
+        assert_eq!(
+            toc(
+                &HEADERS,
+                TocOptions::default()
+                    .min_level(HeaderLevel::Three)
+                    .max_level(HeaderLevel::Six)
+            ),
+            // With `E` left out, `F` ends up below `D`
+            ["- [B](#b)", "  - [C](#c)", "- [D](#d)", "  - [F](#f)"]
+        );
+        // An empty range
+        assert!(
+            toc(
+                &HEADERS,
+                TocOptions::default()
+                    .min_level(HeaderLevel::Three)
+                    .max_level(HeaderLevel::Two)
+            )
+            .is_empty()
+        );
+    }
+}
@@ -0,0 +1,9 @@
+use rustdoc_code_formatter::Document;
+
+fn main() {
+    let crates = rustdoc_code_formatter::build(
+        "./crates/rustdoc-code-formatter/examples/test-apis/test_api/Cargo.toml",
+    );
+    let document = Document::new(crates);
+    println!("{}", serde_json::to_string_pretty(&document).unwrap());
+}
@@ -1,25 +1,26 @@
-use rustdoc_code_formatter::ModuleRepr;
+use rustdoc_code_formatter::{FnRepr, StructRepr, TraitRepr, Visitor};
 
 fn main() {
     let modules = rustdoc_code_formatter::build(
         "./crates/rustdoc-code-formatter/examples/test-apis/test_api/Cargo.toml",
     );
-    for module in modules {
-        print_module(&module);
+    for module in &modules {
+        Printer.visit_module(module);
     }
 }
 
-fn print_module(module: &ModuleRepr) {
-    for function in &module.functions {
+struct Printer;
+
+impl Visitor<'_> for Printer {
+    fn visit_fn(&mut self, function: &FnRepr) {
         println!("{}\n", function.repr);
     }
-    for struct_ in &module.structs {
+
+    fn visit_struct(&mut self, struct_: &StructRepr) {
         println!("{}\n", struct_.repr);
     }
-    for trait_ in &module.traits {
+
+    fn visit_trait(&mut self, trait_: &TraitRepr) {
         println!("{}\n", trait_.repr);
     }
-    for module in &module.modules {
-        print_module(module);
-    }
 }
@@ -0,0 +1,19 @@
+pub struct Struct {
+    pub a: usize,
+}
+
+pub fn function(v1_param: Struct) {}
+
+pub fn removed() {}
+
+pub fn renamed<T: Clone>(value: T) -> T {
+    value
+}
+
+pub trait Trait {
+    fn required(&self);
+
+    fn generic<'a, T>(&self, value: &'a T) -> &'a T {
+        value
+    }
+}
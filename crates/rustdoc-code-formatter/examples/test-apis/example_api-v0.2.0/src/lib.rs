@@ -0,0 +1,24 @@
+pub struct Struct {
+    pub a: usize,
+    pub b: usize,
+}
+
+pub struct StructV2 {
+    pub field: usize,
+}
+
+pub fn function(v1_param: Struct, v2_param: usize) {}
+
+pub fn renamed<U: Clone>(other: U) -> U {
+    other
+}
+
+pub trait Trait {
+    fn required(&self);
+
+    fn generic<'b, U>(&self, other: &'b U) -> &'b U {
+        other
+    }
+
+    fn added_required(&self);
+}
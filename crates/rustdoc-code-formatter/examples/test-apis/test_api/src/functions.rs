@@ -101,3 +101,27 @@ pub async fn async_fn() {}
 pub async fn async_fn_ret_bool() -> bool {
     true
 }
+
+pub fn impl_into(s: impl Into<String>) -> String {
+    s.into()
+}
+
+pub fn impl_ref_multiple(d: &(impl Debug + Send)) {}
+
+pub fn impl_higher_ranked(f: impl for<'a> Fn(&'a str) -> &'a str) {}
+
+pub fn impl_generic_arg(v: Vec<impl Display>) {}
+
+pub fn impl_with_where<T>(i: impl Iterator<Item = T>)
+where
+    T: Debug,
+{
+}
+
+pub fn impl_assoc_bound(i: impl Iterator<Item: Debug>) {}
+
+pub fn impl_precise_capturing<'a, T>(t: &'a T) -> impl Sized + use<'a, T> {
+    t
+}
+
+pub fn maybe_sized<T: ?Sized>(t: &T) {}
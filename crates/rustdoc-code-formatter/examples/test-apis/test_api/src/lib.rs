@@ -1,3 +1,6 @@
+// `use<..>` bounds are only stable from 1.82
+#![feature(precise_capturing)]
+
 pub mod functions;
 pub mod structs;
 pub mod traits;
@@ -53,3 +53,21 @@ pub trait GenericAssociatedTypes {
     type SimpleBound: Simple;
     type WithLifetime<'a>;
 }
+
+pub trait WithProvidedMethod {
+    fn required(&self);
+
+    fn provided(&self) {}
+}
+
+pub trait MixedItems {
+    fn provided(&self) {}
+
+    const DEFAULT: u8 = 0;
+
+    fn required(&self);
+
+    type Type;
+
+    const REQUIRED: u8;
+}
@@ -0,0 +1,22 @@
+use core_api::config::Config;
+use core_api::{Hidden, Output};
+
+pub fn load(config: Config) -> Output {
+    Output {
+        value: config.name.len() as u32,
+    }
+}
+
+pub fn hidden(hidden: Hidden) {}
+
+pub fn build(builder: core_api::Builder, structs: core_api::Structs) {}
+
+pub struct App {
+    pub config: Config,
+}
+
+pub mod nested {
+    pub fn default_config() -> core_api::config::Config {
+        core_api::config::default_config()
+    }
+}
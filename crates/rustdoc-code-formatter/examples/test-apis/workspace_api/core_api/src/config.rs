@@ -0,0 +1,9 @@
+pub struct Config {
+    pub name: String,
+}
+
+pub fn default_config() -> Config {
+    Config {
+        name: String::new(),
+    }
+}
@@ -0,0 +1,20 @@
+pub mod config;
+
+mod inner {
+    pub struct Hidden;
+}
+
+pub use inner::Hidden;
+
+pub struct Output {
+    pub value: u32,
+}
+
+pub fn builder() -> Builder {
+    Builder
+}
+
+pub struct Builder;
+
+/// Named like the section header
+pub struct Structs;
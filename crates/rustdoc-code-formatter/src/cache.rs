@@ -0,0 +1,260 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use rustdoc_types::Crate;
+use sha2::{Digest, Sha256};
+
+use crate::parse_crate;
+
+/// Everything that can change the JSON rustdoc generates for a crate
+pub(crate) struct CacheKey<'a> {
+    pub(crate) manifest_path: &'a Path,
+    pub(crate) toolchain: &'a str,
+    /// The feature flags passed to rustdoc, e.g. `--all-features`
+    pub(crate) features: &'a str,
+    /// Directories of the crates it depends on through `path` dependencies
+    pub(crate) path_dependencies: &'a [PathBuf],
+}
+
+impl CacheKey<'_> {
+    /// `cache_dir` is left out of the hash, since it may sit inside one of the hashed directories
+    fn hash(&self, cache_dir: &Path) -> String {
+        let mut hasher = Sha256::new();
+        hash_field(
+            &mut hasher,
+            self.manifest_path.as_os_str().as_encoded_bytes(),
+        );
+        hash_field(&mut hasher, toolchain_version(self.toolchain).as_bytes());
+        hash_field(&mut hasher, self.features.as_bytes());
+
+        let crate_dir = self.manifest_path.parent().unwrap();
+        // The lockfile lives in the workspace root, which may be above the crate
+        if let Some(lockfile) = crate_dir
+            .ancestors()
+            .map(|dir| dir.join("Cargo.lock"))
+            .find(|lockfile| lockfile.is_file())
+        {
+            hash_field(&mut hasher, &fs::read(lockfile).unwrap());
+        }
+
+        // It only contains cache entries once it exists
+        let cache_dir = fs::canonicalize(cache_dir).ok();
+        let exclude = cache_dir.as_deref();
+        hash_dir(&mut hasher, crate_dir, exclude);
+        for dir in self.path_dependencies {
+            hash_field(&mut hasher, dir.as_os_str().as_encoded_bytes());
+            hash_dir(&mut hasher, dir, exclude);
+        }
+
+        let mut hash = String::new();
+        for byte in hasher.finalize() {
+            write!(hash, "{byte:02x}").unwrap();
+        }
+        hash
+    }
+}
+
+/// Returns the cached docs for `key` if the inputs haven't changed since they were stored,
+/// otherwise runs `build` and stores the JSON at the path it returns. Entries that can't be read
+/// or parsed are rebuilt.
+pub(crate) fn load_or_build(
+    cache_dir: &Path,
+    crate_name: &str,
+    key: &CacheKey,
+    build: impl FnOnce() -> PathBuf,
+) -> Crate {
+    let cache_path = cache_dir.join(format!("{crate_name}-{}.json", key.hash(cache_dir)));
+    let cached = fs::read_to_string(&cache_path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok());
+    if let Some(crate_docs) = cached {
+        return crate_docs;
+    }
+
+    let json = fs::read_to_string(build()).unwrap();
+    let crate_docs = parse_crate(&json);
+    fs::create_dir_all(cache_dir).unwrap();
+    // Write to a temporary file first so a concurrent build never reads a partial entry
+    let tmp_path = cache_path.with_extension("json.tmp");
+    fs::write(&tmp_path, &json).unwrap();
+    fs::rename(tmp_path, cache_path).unwrap();
+    crate_docs
+}
+
+// Prefixed with the length so the boundaries between fields can't be shifted
+fn hash_field(hasher: &mut Sha256, bytes: &[u8]) {
+    hasher.update((bytes.len() as u64).to_le_bytes());
+    hasher.update(bytes);
+}
+
+fn toolchain_version(toolchain: &str) -> String {
+    let output = Command::new("rustdoc")
+        .arg(format!("+{toolchain}"))
+        .arg("--version")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+fn hash_dir(hasher: &mut Sha256, dir: &Path, exclude: Option<&Path>) {
+    // Canonical so the paths walked can be compared with `exclude`
+    let dir = fs::canonicalize(dir).unwrap();
+    let mut files = Vec::new();
+    source_files(&dir, exclude, &mut files);
+    files.sort();
+    for file in files {
+        let relative = file.strip_prefix(&dir).unwrap();
+        hash_field(hasher, relative.as_os_str().as_encoded_bytes());
+        hash_field(hasher, &fs::read(&file).unwrap());
+    }
+}
+
+// Every file in the crate except build output and hidden directories like `.git`, since files
+// such as READMEs can be pulled into the docs with `include_str!`
+fn source_files(dir: &Path, exclude: Option<&Path>, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let entry = entry.unwrap();
+        let path = entry.path();
+        let name = entry.file_name();
+        if name.to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let file_type = entry.file_type().unwrap();
+        if file_type.is_dir() {
+            if name != "target" && Some(path.as_path()) != exclude {
+                source_files(&path, exclude, files);
+            }
+        } else if file_type.is_file() {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use rustdoc_types::{Crate, FORMAT_VERSION, Id};
+
+    use super::{CacheKey, load_or_build};
+    use crate::TOOLCHAIN;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rustdoc-cache-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_crate(dir: &Path, source: &str) {
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("Cargo.toml"), "[package]\nname = \"test\"\n").unwrap();
+        fs::write(dir.join("src/lib.rs"), source).unwrap();
+    }
+
+    fn empty_crate() -> Crate {
+        Crate {
+            root: Id("0:0".to_string()),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: FORMAT_VERSION,
+        }
+    }
+
+    #[test]
+    fn hash_changes_with_path_dependencies() {
+        let dir = temp_dir("dependencies");
+        write_crate(&dir.join("app"), "pub use dep::Item;");
+        write_crate(&dir.join("dep"), "pub struct Item;");
+        let manifest_path = dir.join("app/Cargo.toml");
+        let path_dependencies = [dir.join("dep")];
+        let key = CacheKey {
+            manifest_path: &manifest_path,
+            toolchain: TOOLCHAIN,
+            features: "",
+            path_dependencies: &path_dependencies,
+        };
+
+        let cache_dir = dir.join("cache");
+        let before = key.hash(&cache_dir);
+        assert_eq!(key.hash(&cache_dir), before);
+        fs::write(dir.join("dep/src/lib.rs"), "pub struct Item(u8);").unwrap();
+        assert_ne!(key.hash(&cache_dir), before);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rebuilds_corrupt_entries() {
+        let dir = temp_dir("corrupt");
+        write_crate(&dir.join("app"), "");
+        let manifest_path = dir.join("app/Cargo.toml");
+        let key = CacheKey {
+            manifest_path: &manifest_path,
+            toolchain: TOOLCHAIN,
+            features: "",
+            path_dependencies: &[],
+        };
+        let cache_dir = dir.join("cache");
+        fs::create_dir_all(&cache_dir).unwrap();
+        // A build that was cut off halfway
+        fs::write(
+            cache_dir.join(format!("app-{}.json", key.hash(&cache_dir))),
+            "{\"root\":",
+        )
+        .unwrap();
+        let json_path = dir.join("app.json");
+        fs::write(&json_path, serde_json::to_string(&empty_crate()).unwrap()).unwrap();
+
+        let mut builds = 0;
+        let crate_docs = load_or_build(&cache_dir, "app", &key, || {
+            builds += 1;
+            json_path.clone()
+        });
+        assert_eq!(builds, 1);
+        assert_eq!(crate_docs, empty_crate());
+
+        // The rebuilt entry replaced the corrupt one
+        load_or_build(&cache_dir, "app", &key, || unreachable!());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn cache_dir_inside_the_crate() {
+        let dir = temp_dir("in-crate");
+        write_crate(&dir.join("app"), "");
+        // Not canonical, to check the comparison still finds it
+        let manifest_path = dir.join("app/../app/Cargo.toml");
+        let key = CacheKey {
+            manifest_path: &manifest_path,
+            toolchain: TOOLCHAIN,
+            features: "",
+            path_dependencies: &[],
+        };
+        let cache_dir = dir.join("app/./rustdoc-cache");
+        let json_path = dir.join("app.json");
+        fs::write(&json_path, serde_json::to_string(&empty_crate()).unwrap()).unwrap();
+
+        let mut builds = 0;
+        load_or_build(&cache_dir, "app", &key, || {
+            builds += 1;
+            json_path.clone()
+        });
+        assert_eq!(builds, 1);
+        // Writing the entry didn't change the key
+        let crate_docs = load_or_build(&cache_dir, "app", &key, || unreachable!());
+        assert_eq!(crate_docs, empty_crate());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
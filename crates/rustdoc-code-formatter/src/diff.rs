@@ -0,0 +1,278 @@
+use std::collections::BTreeMap;
+
+use crate::{ItemKind, ItemRepr, ModuleRepr};
+
+/// The differences between the public API of two versions of a crate, sorted by path
+#[derive(Debug, Default)]
+pub struct ApiDiff {
+    pub added: Vec<DiffItem>,
+    pub removed: Vec<DiffItem>,
+    pub changed: Vec<ChangedItem>,
+}
+
+impl ApiDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Debug)]
+pub struct DiffItem {
+    pub path: Vec<String>,
+    pub kind: ItemKind,
+    pub signature: String,
+}
+
+#[derive(Debug)]
+pub struct ChangedItem {
+    pub path: Vec<String>,
+    pub kind: ItemKind,
+    pub before: String,
+    pub after: String,
+}
+
+/// Compares every item in `old` and `new` by its path. Traits are compared by their header only,
+/// their items are compared separately.
+pub fn diff(old: &ModuleRepr, new: &ModuleRepr) -> ApiDiff {
+    let old = signatures(old);
+    let mut new = signatures(new);
+
+    let mut diff = ApiDiff::default();
+    for ((path, kind), before) in old {
+        match new.remove(&(path.clone(), kind)) {
+            Some(after) if after != before => diff.changed.push(ChangedItem {
+                path,
+                kind,
+                before,
+                after,
+            }),
+            Some(_) => {}
+            None => diff.removed.push(DiffItem {
+                path,
+                kind,
+                signature: before,
+            }),
+        }
+    }
+    // Anything left over only exists in the new version
+    for ((path, kind), signature) in new {
+        diff.added.push(DiffItem {
+            path,
+            kind,
+            signature,
+        });
+    }
+    diff
+}
+
+// Keyed by kind too since types, values and macros live in different namespaces, e.g. a module
+// and a function can share a path
+fn signatures(module: &ModuleRepr) -> BTreeMap<(Vec<String>, ItemKind), String> {
+    module
+        .items()
+        .map(|item| ((item.path().to_vec(), item.kind()), signature(item)))
+        .collect()
+}
+
+fn signature(item: ItemRepr) -> String {
+    match item {
+        ItemRepr::Module(module) => format!("mod {}", module.name),
+        ItemRepr::Fn(func) => func.repr.clone(),
+        ItemRepr::Struct(struct_) => struct_.repr.clone(),
+        ItemRepr::Trait(trait_) => trait_header(&trait_.repr),
+        ItemRepr::TraitItem(item) => item.repr.clone(),
+    }
+}
+
+// Everything up to the opening brace of the body, the header may be broken across lines. Empty
+// traits are closed on the same line, e.g. `pub trait Empty {}`, which is kept as `pub trait
+// Empty {` so adding the first item doesn't change the header.
+fn trait_header(repr: &str) -> String {
+    let mut header = String::new();
+    for line in repr.lines() {
+        let line = line
+            .strip_suffix('}')
+            .filter(|line| line.ends_with('{'))
+            .unwrap_or(line);
+        header += line;
+        if line.ends_with('{') {
+            break;
+        }
+        header.push('\n');
+    }
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use rustdoc_types::Id;
+
+    use super::{diff, trait_header};
+    use crate::{FnRepr, ItemKind, ModuleRepr, TraitItemKind, TraitItemRepr, TraitRepr};
+
+    fn path(path: &str) -> Vec<String> {
+        path.split("::").map(str::to_string).collect()
+    }
+
+    fn module(name: &str, functions: Vec<FnRepr>, traits: Vec<TraitRepr>) -> ModuleRepr {
+        ModuleRepr {
+            id: Id(name.to_string()),
+            name: name.to_string(),
+            path: path(&format!("api::{name}")),
+            span: None,
+            functions,
+            structs: Vec::new(),
+            traits,
+            modules: Vec::new(),
+        }
+    }
+
+    fn crate_(
+        functions: Vec<FnRepr>,
+        traits: Vec<TraitRepr>,
+        modules: Vec<ModuleRepr>,
+    ) -> ModuleRepr {
+        ModuleRepr {
+            path: path("api"),
+            modules,
+            ..module("api", functions, traits)
+        }
+    }
+
+    fn function(name: &str, repr: &str) -> FnRepr {
+        FnRepr {
+            id: Id(name.to_string()),
+            name: name.to_string(),
+            path: path(&format!("api::{name}")),
+            span: None,
+            repr: repr.to_string(),
+            signature: repr.to_string(),
+            is_unsafe: false,
+            generics: Vec::new(),
+            input_types: Vec::new(),
+            output_types: Vec::new(),
+        }
+    }
+
+    fn trait_(repr: &str, items: Vec<TraitItemRepr>) -> TraitRepr {
+        TraitRepr {
+            id: Id("T".to_string()),
+            name: "T".to_string(),
+            path: path("api::T"),
+            span: None,
+            repr: repr.to_string(),
+            signature: String::new(),
+            generics: Vec::new(),
+            items,
+            dyn_compatible: true,
+        }
+    }
+
+    fn method(repr: &str) -> TraitItemRepr {
+        TraitItemRepr {
+            id: Id("a".to_string()),
+            name: "a".to_string(),
+            path: path("api::T::a"),
+            span: None,
+            repr: repr.to_string(),
+            signature: repr.to_string(),
+            kind: TraitItemKind::Fn,
+            required: true,
+            input_types: Vec::new(),
+            output_types: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn added_removed_and_changed() {
+        let old = crate_(
+            vec![
+                function("kept", "pub fn kept()"),
+                function("removed", "pub fn removed()"),
+                function("changed", "pub fn changed(x: u8)"),
+            ],
+            Vec::new(),
+            Vec::new(),
+        );
+        let new = crate_(
+            vec![
+                function("kept", "pub fn kept()"),
+                function("changed", "pub fn changed(x: u16)"),
+                function("added", "pub fn added()"),
+            ],
+            Vec::new(),
+            Vec::new(),
+        );
+        let diff = diff(&old, &new);
+
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].path, path("api::added"));
+        assert_eq!(diff.added[0].signature, "pub fn added()");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].path, path("api::removed"));
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].path, path("api::changed"));
+        assert_eq!(diff.changed[0].before, "pub fn changed(x: u8)");
+        assert_eq!(diff.changed[0].after, "pub fn changed(x: u16)");
+    }
+
+    #[test]
+    fn same_path_with_another_kind() {
+        // A function replaced by a module of the same name
+        let old = crate_(vec![function("a", "pub fn a()")], Vec::new(), Vec::new());
+        let new = crate_(
+            Vec::new(),
+            Vec::new(),
+            vec![module("a", Vec::new(), Vec::new())],
+        );
+        let diff = diff(&old, &new);
+
+        assert!(diff.changed.is_empty());
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].kind, ItemKind::Fn);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].kind, ItemKind::Module);
+        assert_eq!(diff.added[0].signature, "mod a");
+    }
+
+    #[test]
+    fn trait_items_are_compared_separately() {
+        let old = crate_(
+            Vec::new(),
+            vec![trait_("pub trait T {}", Vec::new())],
+            Vec::new(),
+        );
+        let new = crate_(
+            Vec::new(),
+            vec![trait_(
+                "pub trait T {\n    fn a();\n}",
+                vec![method("fn a();")],
+            )],
+            Vec::new(),
+        );
+        let diff = diff(&old, &new);
+
+        // The header of the empty trait is the same
+        assert!(diff.changed.is_empty(), "{diff:?}");
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].path, path("api::T::a"));
+    }
+
+    #[test]
+    fn trait_headers() {
+        assert_eq!(trait_header("pub trait Empty {}"), "pub trait Empty {");
+        assert_eq!(
+            trait_header("pub trait T {\n    fn a();\n}"),
+            "pub trait T {"
+        );
+        assert_eq!(
+            trait_header("pub trait T<U>\nwhere\n    U: Clone,\n{\n    fn a();\n}"),
+            "pub trait T<U>\nwhere\n    U: Clone,\n{"
+        );
+        assert_eq!(
+            trait_header("pub trait T<U>\nwhere\n    U: Clone,\n{}"),
+            "pub trait T<U>\nwhere\n    U: Clone,\n{"
+        );
+    }
+}
@@ -1,8 +1,33 @@
+mod cache;
+mod diff;
+mod mentions;
+mod query;
+mod reexports;
+#[cfg(feature = "serde")]
+mod schema;
+mod semver;
+mod signature;
+pub mod visit;
+mod workspace;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+pub use diff::{ApiDiff, ChangedItem, DiffItem, diff};
+use mentions::Mentions;
+pub use query::Query;
+pub use rustdoc_types::Id;
 use rustdoc_types::{
-    Constant, Crate, DynTrait, GenericArg, GenericArgs, GenericBound, GenericParamDef,
-    GenericParamDefKind, Generics, Item, ItemEnum, Path, PolyTrait, StructKind, Term,
+    Constant, Crate, DynTrait, Function, GenericArg, GenericArgs, GenericBound, GenericParamDef,
+    GenericParamDefKind, Generics, Item, ItemEnum, Path, PolyTrait, Span, StructKind, Term,
     TraitBoundModifier, Type, TypeBinding, TypeBindingKind, Visibility, WherePredicate,
 };
+#[cfg(feature = "serde")]
+pub use schema::{Document, SCHEMA_VERSION};
+pub use semver::{SemverChange, SemverImpact, SemverReport, semver_check};
+use signature::{Signature, SignatureEnd, indent_continuation, number_generics};
+pub use visit::{Visitor, VisitorMut};
 
 #[derive(Debug)]
 enum CrateRepr {
@@ -16,8 +41,13 @@ enum CrateRepr {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ModuleRepr {
+    pub id: Id,
     pub name: String,
+    /// Segments of the path the item is reachable at, starting with the crate name
+    pub path: Vec<String>,
+    pub span: Option<SourceSpan>,
     pub functions: Vec<FnRepr>,
     pub structs: Vec<StructRepr>,
     pub traits: Vec<TraitRepr>,
@@ -25,75 +55,542 @@ pub struct ModuleRepr {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FnRepr {
+    pub id: Id,
     pub name: String,
+    /// Segments of the path the item is reachable at, starting with the crate name
+    pub path: Vec<String>,
+    pub span: Option<SourceSpan>,
     pub repr: String,
+    /// The signature without argument names and with generic params numbered by position, so
+    /// it only changes when the function does for callers
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub signature: String,
+    /// Whether it's an `unsafe fn`, so callers have to uphold its safety requirements
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub is_unsafe: bool,
+    pub generics: Vec<GenericParamRepr>,
+    /// Types and traits named in the arguments and generic bounds
+    pub input_types: Vec<TypeRef>,
+    pub output_types: Vec<TypeRef>,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StructRepr {
+    pub id: Id,
     pub name: String,
+    /// Segments of the path the item is reachable at, starting with the crate name
+    pub path: Vec<String>,
+    pub span: Option<SourceSpan>,
     pub repr: String,
+    /// The header without the generic params and fields, with generic params numbered, see
+    /// [`FnRepr::signature`]. Params and fields are compared one by one instead.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub signature: String,
+    pub generics: Vec<GenericParamRepr>,
+    /// Visible fields, hidden tuple fields are shown as `_`
+    pub fields: Vec<String>,
+    pub has_private_fields: bool,
+    pub non_exhaustive: bool,
+    /// Auto traits like `Send` and `Sync` that the struct implements
+    pub auto_traits: Vec<String>,
+    /// Types and traits named in the visible fields
+    pub field_types: Vec<TypeRef>,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraitRepr {
+    pub id: Id,
+    pub name: String,
+    /// Segments of the path the item is reachable at, starting with the crate name
+    pub path: Vec<String>,
+    pub span: Option<SourceSpan>,
+    pub repr: String,
+    /// The header without the generic params and items, with generic params numbered, see
+    /// [`FnRepr::signature`]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub signature: String,
+    pub generics: Vec<GenericParamRepr>,
+    pub items: Vec<TraitItemRepr>,
+    /// Whether the trait can be used as `dyn Trait`, formerly known as object safety. Read from
+    /// rustdoc's `is_object_safe`, which is called `is_dyn_compatible` in format versions after
+    /// the supported one.
+    pub dyn_compatible: bool,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TraitItemRepr {
+    pub id: Id,
     pub name: String,
+    /// Segments of the path the item is reachable at, starting with the crate name
+    pub path: Vec<String>,
+    pub span: Option<SourceSpan>,
     pub repr: String,
+    /// The signature with the generic params of the trait and the item numbered, see
+    /// [`FnRepr::signature`]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub signature: String,
+    pub kind: TraitItemKind,
+    /// Whether implementors must provide this item, i.e. it has no default body or value
+    pub required: bool,
+    /// Types and traits named in the arguments and generic bounds of a method
+    pub input_types: Vec<TypeRef>,
+    /// Types and traits named in the return type of a method, the type of a constant or the
+    /// bounds and default of a type
+    pub output_types: Vec<TypeRef>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GenericParamRepr {
+    /// Includes the leading `'` for lifetimes
+    pub name: String,
+    /// The declaration with its bounds and default, e.g. `T: Clone = u8`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub repr: String,
+    pub has_default: bool,
+}
+
+/// A type or trait named in a signature, resolved to the item it refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypeRef {
+    pub id: Id,
+    /// Full path of the item where it's defined, e.g. `["alloc", "string", "String"]`. Items of
+    /// the crates in the workspace have the public path they're re-exported at instead.
+    pub path: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TraitItemKind {
+    Fn,
+    AssocConst,
+    AssocType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ItemKind {
+    Module,
+    Fn,
+    Struct,
+    Trait,
+    TraitItem(TraitItemKind),
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            ItemKind::Module => "module",
+            ItemKind::Fn => "function",
+            ItemKind::Struct => "struct",
+            ItemKind::Trait => "trait",
+            ItemKind::TraitItem(TraitItemKind::Fn) => "method",
+            ItemKind::TraitItem(TraitItemKind::AssocConst) => "associated constant",
+            ItemKind::TraitItem(TraitItemKind::AssocType) => "associated type",
+        };
+        f.write_str(kind)
+    }
+}
+
+/// Where an item is defined, missing for items that come from macro expansions
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourceSpan {
+    /// Relative to the directory cargo runs rustdoc from, which is the workspace root for
+    /// workspace members and the directory of the crate's manifest otherwise
+    pub filename: PathBuf,
+    /// Lines and columns are 1-based and the end is inclusive, like they're shown in editors
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl From<&Span> for SourceSpan {
+    fn from(span: &Span) -> Self {
+        // rustdoc lines are already 1-based but columns are 0-based with an exclusive end
+        Self {
+            filename: span.filename.clone(),
+            start_line: span.begin.0,
+            start_column: span.begin.1 + 1,
+            end_line: span.end.0,
+            end_column: span.end.1,
+        }
+    }
+}
+
+impl fmt::Display for SourceSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.filename.display(), self.start_line)
+    }
+}
+
+/// A borrowed item of any kind, as yielded by [`ModuleRepr::items`]
+#[derive(Debug, Clone, Copy)]
+pub enum ItemRepr<'a> {
+    Module(&'a ModuleRepr),
+    Fn(&'a FnRepr),
+    Struct(&'a StructRepr),
+    Trait(&'a TraitRepr),
+    TraitItem(&'a TraitItemRepr),
+}
+
+impl<'a> ItemRepr<'a> {
+    pub fn id(&self) -> &'a Id {
+        match self {
+            ItemRepr::Module(module) => &module.id,
+            ItemRepr::Fn(func) => &func.id,
+            ItemRepr::Struct(struct_) => &struct_.id,
+            ItemRepr::Trait(trait_) => &trait_.id,
+            ItemRepr::TraitItem(item) => &item.id,
+        }
+    }
+
+    pub fn name(&self) -> &'a str {
+        match self {
+            ItemRepr::Module(module) => &module.name,
+            ItemRepr::Fn(func) => &func.name,
+            ItemRepr::Struct(struct_) => &struct_.name,
+            ItemRepr::Trait(trait_) => &trait_.name,
+            ItemRepr::TraitItem(item) => &item.name,
+        }
+    }
+
+    pub fn path(&self) -> &'a [String] {
+        match self {
+            ItemRepr::Module(module) => &module.path,
+            ItemRepr::Fn(func) => &func.path,
+            ItemRepr::Struct(struct_) => &struct_.path,
+            ItemRepr::Trait(trait_) => &trait_.path,
+            ItemRepr::TraitItem(item) => &item.path,
+        }
+    }
+
+    pub fn kind(&self) -> ItemKind {
+        match self {
+            ItemRepr::Module(_) => ItemKind::Module,
+            ItemRepr::Fn(_) => ItemKind::Fn,
+            ItemRepr::Struct(_) => ItemKind::Struct,
+            ItemRepr::Trait(_) => ItemKind::Trait,
+            ItemRepr::TraitItem(item) => ItemKind::TraitItem(item.kind),
+        }
+    }
+
+    pub fn span(&self) -> Option<&'a SourceSpan> {
+        match self {
+            ItemRepr::Module(module) => module.span.as_ref(),
+            ItemRepr::Fn(func) => func.span.as_ref(),
+            ItemRepr::Struct(struct_) => struct_.span.as_ref(),
+            ItemRepr::Trait(trait_) => trait_.span.as_ref(),
+            ItemRepr::TraitItem(item) => item.span.as_ref(),
+        }
+    }
+}
+
+impl ModuleRepr {
+    /// Iterates depth first over this module and every item nested beneath it, including trait
+    /// items
+    pub fn items(&self) -> Items<'_> {
+        Items {
+            stack: vec![ItemRepr::Module(self)],
+        }
+    }
+}
+
+pub struct Items<'a> {
+    stack: Vec<ItemRepr<'a>>,
+}
+
+impl<'a> Iterator for Items<'a> {
+    type Item = ItemRepr<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.stack.pop()?;
+        match item {
+            ItemRepr::Module(module) => {
+                // Pushed in reverse so they're popped in declaration order
+                self.stack
+                    .extend(module.modules.iter().rev().map(ItemRepr::Module));
+                self.stack
+                    .extend(module.traits.iter().rev().map(ItemRepr::Trait));
+                self.stack
+                    .extend(module.structs.iter().rev().map(ItemRepr::Struct));
+                self.stack
+                    .extend(module.functions.iter().rev().map(ItemRepr::Fn));
+            }
+            ItemRepr::Trait(trait_) => {
+                self.stack
+                    .extend(trait_.items.iter().rev().map(ItemRepr::TraitItem));
+            }
+            ItemRepr::Fn(_) | ItemRepr::Struct(_) | ItemRepr::TraitItem(_) => {}
+        }
+        Some(item)
+    }
 }
 
 trait ToRepr {
-    fn to_repr(&self) -> String;
+    fn to_repr(&self, ctx: &Context) -> String;
 }
 
-pub fn build(path: &str) -> Vec<ModuleRepr> {
-    let json_path = rustdoc_json::Builder::default()
-        .toolchain("nightly")
-        .all_features(true)
-        .manifest_path(path)
-        .build()
-        .unwrap();
-    let json_string = std::fs::read_to_string(json_path).unwrap();
-    let crate_docs: rustdoc_types::Crate = serde_json::from_str(&json_string).unwrap();
-
-    let modules: Vec<_> = crate_docs
-        .index
-        .iter()
-        .filter_map(|(id, item)| {
-            if *id == crate_docs.root {
-                let res = process_item(&crate_docs, item, false);
-                let CrateRepr::Module(module) = res.unwrap() else {
+/// How items are ordered within each module and trait
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ItemOrder {
+    /// The order items are defined in the source, falling back to rustdoc's order for items
+    /// without a span
+    #[default]
+    Source,
+    /// Sorted by name
+    Alphabetical,
+    /// Grouped by kind like rustdoc does, e.g. associated types before methods in traits and
+    /// required methods before provided ones, in source order within each group
+    Kind,
+}
+
+/// A nightly that writes the JSON format rustdoc-types 0.28 reads, format version 32. Newer
+/// nightlies change the format, e.g. they rename `is_object_safe` to `is_dyn_compatible`, so both
+/// have to be updated together.
+const TOOLCHAIN: &str = "nightly-2024-07-25";
+
+#[derive(Debug, Clone)]
+pub struct Builder {
+    manifest_path: PathBuf,
+    order: ItemOrder,
+    max_width: usize,
+    cache_dir: Option<PathBuf>,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            manifest_path: PathBuf::from("Cargo.toml"),
+            order: ItemOrder::default(),
+            // Same default as rustfmt
+            max_width: 100,
+            cache_dir: None,
+        }
+    }
+}
+
+impl Builder {
+    pub fn manifest_path(mut self, manifest_path: impl AsRef<std::path::Path>) -> Self {
+        self.manifest_path = manifest_path.as_ref().to_owned();
+        self
+    }
+
+    pub fn order(mut self, order: ItemOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Function, struct and trait signatures longer than this are broken across lines. Impls
+    /// aren't part of the model, so there are no impl headers to format.
+    pub fn max_width(mut self, max_width: usize) -> Self {
+        self.max_width = max_width;
+        self
+    }
+
+    /// Stores the generated rustdoc JSON in `cache_dir` and reuses it as long as the manifest,
+    /// lockfile, source files, toolchain and features are unchanged, including the sources of
+    /// path dependencies like other crates in the workspace
+    pub fn cache_dir(mut self, cache_dir: impl AsRef<std::path::Path>) -> Self {
+        self.cache_dir = Some(cache_dir.as_ref().to_owned());
+        self
+    }
+
+    /// Documents the crate at the manifest path, or every library in the workspace if it's the
+    /// workspace root. Items from other crates in the workspace are shown with the public path
+    /// they can be named by, which the [`TypeRef`]s of an item can be matched against to link
+    /// them. Items that aren't public are shown with their bare name.
+    pub fn build(self) -> Vec<ModuleRepr> {
+        let members = workspace::members(&self.manifest_path);
+        let workspace_crates: Vec<_> = members
+            .iter()
+            .map(|member| member.crate_name.clone())
+            .collect();
+        let crates: Vec<_> = members
+            .iter()
+            .map(|member| self.load_crate(member))
+            .collect();
+        let mut public_paths = HashMap::new();
+        for crate_docs in &crates {
+            public_paths.extend(reexports::public_paths(crate_docs));
+        }
+        crates
+            .iter()
+            .map(|crate_docs| {
+                let ctx = Context {
+                    crate_docs,
+                    workspace_crates: &workspace_crates,
+                    public_paths: &public_paths,
+                    order: self.order,
+                    max_width: self.max_width,
+                };
+                let root = &crate_docs.index[&crate_docs.root];
+                let CrateRepr::Module(module) = process_item(&ctx, root, false, &[]).unwrap()
+                else {
                     unreachable!()
                 };
-                Some(module)
-            } else {
-                None
+                module
+            })
+            .collect()
+    }
+
+    fn load_crate(&self, member: &workspace::Member) -> Crate {
+        let build_json = || {
+            rustdoc_json::Builder::default()
+                .toolchain(TOOLCHAIN)
+                .all_features(true)
+                .manifest_path(&member.manifest_path)
+                .build()
+                .unwrap()
+        };
+        match &self.cache_dir {
+            Some(cache_dir) => {
+                let key = cache::CacheKey {
+                    manifest_path: &member.manifest_path,
+                    toolchain: TOOLCHAIN,
+                    features: "--all-features",
+                    path_dependencies: &member.path_dependencies,
+                };
+                cache::load_or_build(cache_dir, &member.crate_name, &key, build_json)
             }
+            None => parse_crate(&std::fs::read_to_string(build_json()).unwrap()),
+        }
+    }
+}
+
+fn parse_crate(json: &str) -> Crate {
+    serde_json::from_str(json).unwrap_or_else(|err| {
+        let format_version = serde_json::from_str::<serde_json::Value>(json)
+            .ok()
+            .and_then(|value| value["format_version"].as_u64());
+        match format_version {
+            Some(version) if version != u64::from(rustdoc_types::FORMAT_VERSION) => panic!(
+                "rustdoc JSON format version {version} isn't supported, only {} is",
+                rustdoc_types::FORMAT_VERSION
+            ),
+            _ => panic!("invalid rustdoc JSON: {err}"),
+        }
+    })
+}
+
+pub fn build(path: &str) -> Vec<ModuleRepr> {
+    Builder::default().manifest_path(path).build()
+}
+
+#[derive(Clone, Copy)]
+struct Context<'a> {
+    crate_docs: &'a Crate,
+    workspace_crates: &'a [String],
+    /// Definition paths of the public items of the workspace crates to their public paths
+    public_paths: &'a HashMap<Vec<String>, Vec<String>>,
+    order: ItemOrder,
+    max_width: usize,
+}
+
+impl Context<'_> {
+    /// The public path of an item defined in another crate of the workspace, so it's clear which
+    /// crate it comes from. Items that can't be named from outside that crate get their bare name.
+    fn workspace_path(&self, id: &Id) -> Option<String> {
+        let summary = self.crate_docs.paths.get(id)?;
+        let external_crate = self.crate_docs.external_crates.get(&summary.crate_id)?;
+        if !self.workspace_crates.contains(&external_crate.name) {
+            return None;
+        }
+        match self.public_paths.get(&summary.path) {
+            Some(path) => Some(path.join("::")),
+            None => summary.path.last().cloned(),
+        }
+    }
+
+    /// The items of `parent`, which is a module or trait
+    fn sorted_items(&self, parent: &Item, ids: &[Id]) -> Vec<&Item> {
+        let items: Vec<_> = ids.iter().map(|id| &self.crate_docs.index[id]).collect();
+        let source_keys = source_order_keys(parent, &items);
+        let mut items: Vec<_> = items.into_iter().zip(source_keys).collect();
+        match self.order {
+            ItemOrder::Source => items.sort_by_key(|(_, key)| *key),
+            ItemOrder::Alphabetical => {
+                items.sort_by(|(a, _), (b, _)| (&a.name, &a.id.0).cmp(&(&b.name, &b.id.0)));
+            }
+            ItemOrder::Kind => items.sort_by_key(|(item, key)| (kind_order_key(item), *key)),
+        }
+        items.into_iter().map(|(item, _)| item).collect()
+    }
+}
+
+/// Sorts by position in the parent's file. Items defined in another file, like out-of-line
+/// modules, only have a span in that file, so they're kept right after the item rustdoc lists
+/// before them. Items without a span sort last in rustdoc's order.
+type SourceKey = (bool, (usize, usize), usize);
+
+fn source_order_keys(parent: &Item, items: &[&Item]) -> Vec<SourceKey> {
+    let parent_file = parent.span.as_ref().map(|span| &span.filename);
+    let mut previous = (0, 0);
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| match &item.span {
+            Some(span) if Some(&span.filename) == parent_file => {
+                previous = span.begin;
+                (false, span.begin, i)
+            }
+            Some(_) => (false, previous, i),
+            None => (true, (0, 0), i),
         })
-        .collect();
+        .collect()
+}
 
-    modules
+fn kind_order_key(item: &Item) -> u8 {
+    match &item.inner {
+        ItemEnum::Module(_) => 0,
+        ItemEnum::AssocType { default: None, .. } => 1,
+        ItemEnum::AssocType { .. } => 2,
+        ItemEnum::AssocConst { default: None, .. } => 3,
+        ItemEnum::AssocConst { .. } => 4,
+        ItemEnum::Struct(_) => 5,
+        ItemEnum::Trait(_) => 6,
+        ItemEnum::Function(func) if !func.has_body => 7,
+        ItemEnum::Function(_) => 8,
+        _ => 9,
+    }
 }
 
-fn process_item(crate_docs: &Crate, item: &Item, allow_non_public: bool) -> Option<CrateRepr> {
+fn process_item(
+    ctx: &Context,
+    item: &Item,
+    allow_non_public: bool,
+    parent_path: &[String],
+) -> Option<CrateRepr> {
     if !(item.visibility == Visibility::Public
         || (item.visibility == Visibility::Default && allow_non_public))
     {
         return None;
     }
+    let mut path = parent_path.to_vec();
+    path.extend(item.name.clone());
+    let span = item.span.as_ref().map(SourceSpan::from);
     match &item.inner {
         ItemEnum::Module(module) => {
             let mut repr = ModuleRepr {
+                id: item.id.clone(),
                 name: item.name.clone().unwrap(),
+                path: path.clone(),
+                span,
                 functions: Vec::new(),
                 structs: Vec::new(),
                 traits: Vec::new(),
                 modules: Vec::new(),
             };
             // let mut res = Vec::new();
-            for id in &module.items {
-                let item = &crate_docs.index[id];
-                let item = process_item(crate_docs, item, allow_non_public);
+            for item in ctx.sorted_items(item, &module.items) {
+                let item = process_item(ctx, item, allow_non_public, &path);
                 if let Some(item) = item {
                     match item {
                         CrateRepr::Module(module) => {
@@ -114,33 +611,36 @@ fn process_item(crate_docs: &Crate, item: &Item, allow_non_public: bool) -> Opti
             }
             Some(CrateRepr::Module(repr))
         }
-        ItemEnum::ExternCrate { name, rename } => todo!(),
+        ItemEnum::ExternCrate { .. } => todo!(),
         ItemEnum::Import(_) => None,
         ItemEnum::Union(_) => todo!(),
         ItemEnum::Struct(struct_) => {
             let name = item.name.clone().unwrap();
-            let non_exhaustive = item
-                .attrs
-                .iter()
-                .find(|a| *a == "#[non_exhaustive]")
-                .map(|s| s.to_string() + "\n")
-                .unwrap_or_default();
-            let (generics, where_clause) = generics_repr(&struct_.generics);
-            let mut vis = item.visibility.to_repr();
+            let non_exhaustive = item.attrs.iter().any(|a| a == "#[non_exhaustive]");
+            let (generics, where_predicates) = generics_lists(ctx, &struct_.generics);
+            let mut vis = item.visibility.to_repr(ctx);
             if !vis.is_empty() {
                 vis += " ";
             }
-            let struct_repr = match &struct_.kind {
-                StructKind::Unit => format!("{vis}struct {name}{generics}{where_clause}"),
+            let mut signature = Signature {
+                head: format!("{vis}struct {name}"),
+                generics,
+                args: None,
+                tail: "".to_string(),
+                where_predicates,
+                end: SignatureEnd::None,
+            };
+            let (struct_repr, fields, has_private_fields) = match &struct_.kind {
+                StructKind::Unit => (signature.format(ctx.max_width), Vec::new(), false),
                 StructKind::Tuple(ids) => {
                     let tuple_fields: Vec<_> = ids
                         .iter()
                         .map(|id| {
                             if let Some(id) = id {
-                                let mut item = crate_docs.index[id].clone();
+                                let mut item = ctx.crate_docs.index[id].clone();
                                 // We don't want to show the numeric names for tuples
                                 item.name = None;
-                                let processed = process_item(crate_docs, &item, false);
+                                let processed = process_item(ctx, &item, false, &path);
                                 match processed.unwrap() {
                                     CrateRepr::StructField(field) => field,
                                     _ => unreachable!(),
@@ -150,22 +650,30 @@ fn process_item(crate_docs: &Crate, item: &Item, allow_non_public: bool) -> Opti
                             }
                         })
                         .collect();
-                    format!(
-                        "{vis}struct {name}{generics}({})",
-                        comma_separated(&tuple_fields)
+                    signature.args = Some(tuple_fields.clone());
+                    let has_private_fields = ids.iter().any(|id| id.is_none());
+                    (
+                        signature.format(ctx.max_width),
+                        tuple_fields,
+                        has_private_fields,
                     )
                 }
                 StructKind::Plain {
                     fields,
                     fields_stripped,
                 } => {
-                    let mut s =
-                        format!("{non_exhaustive}pub struct {name}{generics}{where_clause} {{");
+                    signature.end = SignatureEnd::Brace;
+                    let mut s = if non_exhaustive {
+                        "#[non_exhaustive]\n".to_string()
+                    } else {
+                        "".to_string()
+                    };
+                    s += &signature.format(ctx.max_width);
                     let fields_processed: Vec<_> = fields
                         .iter()
                         .map(|id| {
-                            let item = &crate_docs.index[id];
-                            let processed = process_item(crate_docs, item, false);
+                            let item = &ctx.crate_docs.index[id];
+                            let processed = process_item(ctx, item, false, &path);
 
                             match processed.unwrap() {
                                 CrateRepr::StructField(field) => field,
@@ -184,23 +692,56 @@ fn process_item(crate_docs: &Crate, item: &Item, allow_non_public: bool) -> Opti
                         s += "\n";
                     }
                     s += "}";
-                    s
+                    (s, fields_processed, *fields_stripped)
                 }
             };
+            let header = Signature {
+                generics: Vec::new(),
+                args: signature.args.as_ref().map(|_| vec!["..".to_string()]),
+                ..signature
+            };
+            let struct_signature = number_generics(
+                &header.format(usize::MAX),
+                &generic_names(&struct_.generics),
+            );
+            let mut auto_traits: Vec<_> = struct_
+                .impls
+                .iter()
+                .filter_map(|id| match &ctx.crate_docs.index[id].inner {
+                    // Freeze is an implementation detail of the compiler, rustdoc doesn't show it
+                    ItemEnum::Impl(impl_) if impl_.synthetic && !impl_.negative => impl_
+                        .trait_
+                        .as_ref()
+                        .filter(|trait_| trait_.name != "Freeze")
+                        .map(|trait_| trait_.name.clone()),
+                    _ => None,
+                })
+                .collect();
+            auto_traits.sort();
             Some(CrateRepr::Struct(StructRepr {
+                id: item.id.clone(),
                 name,
+                path,
+                span,
                 repr: struct_repr,
+                signature: struct_signature,
+                generics: generic_params(ctx, &struct_.generics),
+                fields,
+                has_private_fields,
+                non_exhaustive,
+                auto_traits,
+                field_types: mentions::field_types(ctx, struct_),
             }))
         }
         ItemEnum::StructField(ty) => {
-            let mut vis = item.visibility.to_repr();
+            let mut vis = item.visibility.to_repr(ctx);
             if !vis.is_empty() {
                 vis += " ";
             }
             let s = if let Some(name) = &item.name {
-                format!("{vis}{name}: {}", ty.to_repr())
+                format!("{vis}{name}: {}", ty.to_repr(ctx))
             } else {
-                format!("{vis}{}", ty.to_repr())
+                format!("{vis}{}", ty.to_repr(ctx))
             };
             Some(CrateRepr::StructField(s))
         }
@@ -208,82 +749,179 @@ fn process_item(crate_docs: &Crate, item: &Item, allow_non_public: bool) -> Opti
         ItemEnum::Variant(_) => todo!(),
         ItemEnum::Function(func) => {
             let name = item.name.clone().unwrap();
-            let inputs: Vec<_> = func
-                .decl
-                .inputs
-                .iter()
-                .map(|(name, ty)| {
-                    let ty = ty.to_repr();
-                    if name == "self" && ty == "&Self" {
-                        "&self".to_string()
-                    } else if name == "self" && ty == "Self" {
-                        "self".to_string()
-                    } else {
-                        format!("{name}: {ty}")
-                    }
-                })
-                .collect();
-            let inputs = comma_separated(&inputs);
-            let const_ = if func.header.const_ { "const " } else { "" };
-            let async_ = if func.header.async_ { "async " } else { "" };
-            let unsafe_ = if func.header.unsafe_ { "unsafe " } else { "" };
-            let (generics, where_clause) = generics_repr(&func.generics);
-            let output = func
-                .decl
-                .output
-                .as_ref()
-                .map(|o| format!(" -> {}", o.to_repr()))
-                .unwrap_or_default();
-            let mut vis = item.visibility.to_repr();
-            if !vis.is_empty() {
-                vis += " ";
-            }
-            let func = format!(
-                "{vis}{const_}{unsafe_}{async_}fn {name}{generics}({inputs}){output}{where_clause}"
-            );
-            Some(CrateRepr::Fn(FnRepr { name, repr: func }))
+            let repr = fn_signature(ctx, item, func, SignatureEnd::None).format(ctx.max_width);
+            let (input_types, output_types) = mentions::fn_types(ctx, func);
+            Some(CrateRepr::Fn(FnRepr {
+                id: item.id.clone(),
+                name,
+                path,
+                span,
+                repr,
+                signature: normalized_fn_signature(ctx, item, func, &[]),
+                is_unsafe: func.header.unsafe_,
+                generics: generic_params(ctx, &func.generics),
+                input_types,
+                output_types,
+            }))
         }
         ItemEnum::Trait(trait_) => {
             let name = item.name.clone().unwrap();
-            let (generics, where_clause) = generics_repr(&trait_.generics);
+            let (generics, where_predicates) = generics_lists(ctx, &trait_.generics);
             let auto = if trait_.is_auto { "auto " } else { "" };
             let unsafe_ = if trait_.is_unsafe { "unsafe " } else { "" };
-            let mut vis = item.visibility.to_repr();
+            let mut vis = item.visibility.to_repr(ctx);
             if !vis.is_empty() {
                 vis += " ";
             }
-            let mut bounds = plus_separated(&trait_.bounds);
+            let mut bounds = plus_separated(ctx, &trait_.bounds);
             if !bounds.is_empty() {
                 bounds = format!(": {bounds}");
             }
-            let mut s =
-                format!("{vis}{auto}{unsafe_}trait {name}{generics}{bounds}{where_clause} {{");
+            let header = Signature {
+                head: format!("{vis}{auto}{unsafe_}trait {name}"),
+                generics,
+                args: None,
+                tail: bounds,
+                where_predicates,
+                end: SignatureEnd::Brace,
+            };
+            let mut s = header.format(ctx.max_width);
+            let trait_generics = generic_names(&trait_.generics);
+            let header = Signature {
+                generics: Vec::new(),
+                ..header
+            };
+            let signature = number_generics(&header.format(usize::MAX), &trait_generics);
 
-            let items: Vec<_> = trait_
-                .items
-                .iter()
-                .map(|id| {
-                    let item = &crate_docs.index[id];
-                    let processed = process_item(crate_docs, item, true);
-                    match processed.unwrap() {
-                        CrateRepr::Fn(func) => func.repr,
-                        CrateRepr::AssocConst(assoc_const) => assoc_const,
-                        CrateRepr::AssocType(assoc_type) => assoc_type,
+            // Items are nested one level deep inside the trait block
+            let item_ctx = Context {
+                max_width: ctx.max_width.saturating_sub(4),
+                ..*ctx
+            };
+            let items: Vec<_> = ctx
+                .sorted_items(item, &trait_.items)
+                .into_iter()
+                .map(|item| {
+                    let processed = process_item(&item_ctx, item, true, &path);
+                    let id = item.id.clone();
+                    let name = item.name.clone().unwrap();
+                    let mut path = path.clone();
+                    path.push(name.clone());
+                    let span = item.span.as_ref().map(SourceSpan::from);
+                    match (processed.unwrap(), &item.inner) {
+                        (CrateRepr::Fn(_), ItemEnum::Function(function)) => {
+                            let repr = if function.has_body {
+                                fn_signature(ctx, item, function, SignatureEnd::Brace)
+                                    .format(item_ctx.max_width)
+                                    + " ... }"
+                            } else {
+                                fn_signature(ctx, item, function, SignatureEnd::Semicolon)
+                                    .format(item_ctx.max_width)
+                            };
+                            let (input_types, output_types) = mentions::fn_types(ctx, function);
+                            TraitItemRepr {
+                                id,
+                                name,
+                                path,
+                                span,
+                                repr,
+                                signature: normalized_fn_signature(
+                                    ctx,
+                                    item,
+                                    function,
+                                    &trait_generics,
+                                ),
+                                kind: TraitItemKind::Fn,
+                                required: !function.has_body,
+                                input_types,
+                                output_types,
+                            }
+                        }
+                        (
+                            CrateRepr::AssocConst(assoc_const),
+                            ItemEnum::AssocConst { type_, default },
+                        ) => {
+                            let mut output = Vec::new();
+                            type_.mentions(&mut output);
+                            // Without the default, which isn't part of the interface
+                            let signature = number_generics(
+                                &format!("const {name}: {};", type_.to_repr(ctx)),
+                                &trait_generics,
+                            );
+                            TraitItemRepr {
+                                id,
+                                name,
+                                path,
+                                span,
+                                signature,
+                                repr: assoc_const,
+                                kind: TraitItemKind::AssocConst,
+                                required: default.is_none(),
+                                input_types: Vec::new(),
+                                output_types: mentions::type_refs(ctx, output),
+                            }
+                        }
+                        (
+                            CrateRepr::AssocType(assoc_type),
+                            ItemEnum::AssocType {
+                                generics,
+                                bounds,
+                                default,
+                            },
+                        ) => {
+                            let mut input = Vec::new();
+                            generics.mentions(&mut input);
+                            let mut output = Vec::new();
+                            bounds.mentions(&mut output);
+                            if let Some(default) = default {
+                                default.mentions(&mut output);
+                            }
+                            let signature = number_generics(
+                                &assoc_type_signature(ctx, &name, generics, bounds, None)
+                                    .format(usize::MAX),
+                                &trait_generics,
+                            );
+                            TraitItemRepr {
+                                id,
+                                name,
+                                path,
+                                span,
+                                signature,
+                                repr: assoc_type,
+                                kind: TraitItemKind::AssocType,
+                                required: default.is_none(),
+                                input_types: mentions::type_refs(ctx, input),
+                                output_types: mentions::type_refs(ctx, output),
+                            }
+                        }
                         _ => unreachable!(),
                     }
                 })
                 .collect();
             for item in &items {
-                s += &format!("\n    {item}");
+                s += &format!("\n    {}", indent_continuation(&item.repr, "    "));
+            }
+            if !items.is_empty() {
+                s += "\n";
             }
-            s += "\n}";
-            Some(CrateRepr::Trait(TraitRepr { name, repr: s }))
+            s += "}";
+            Some(CrateRepr::Trait(TraitRepr {
+                id: item.id.clone(),
+                name,
+                path,
+                span,
+                repr: s,
+                signature,
+                generics: generic_params(ctx, &trait_.generics),
+                items,
+                dyn_compatible: trait_.is_object_safe,
+            }))
         }
         ItemEnum::TraitAlias(_) => todo!(),
         ItemEnum::Impl(_) => todo!(),
         ItemEnum::TypeAlias(_) => todo!(),
         ItemEnum::OpaqueTy(_) => todo!(),
-        ItemEnum::Constant { type_, const_ } => todo!(),
+        ItemEnum::Constant { .. } => todo!(),
         ItemEnum::Static(_) => todo!(),
         ItemEnum::ForeignType => todo!(),
         ItemEnum::Macro(_) => todo!(),
@@ -291,7 +929,7 @@ fn process_item(crate_docs: &Crate, item: &Item, allow_non_public: bool) -> Opti
         ItemEnum::Primitive(_) => todo!(),
         ItemEnum::AssocConst { type_, default } => {
             let name = item.name.clone().unwrap();
-            let mut s = format!("const {name}: {}", type_.to_repr());
+            let mut s = format!("const {name}: {}", type_.to_repr(ctx));
             if let Some(default) = default {
                 s += &format!(" = {default}");
             }
@@ -305,59 +943,46 @@ fn process_item(crate_docs: &Crate, item: &Item, allow_non_public: bool) -> Opti
             default,
         } => {
             let name = item.name.clone().unwrap();
-
-            let (generics, where_clause) = generics_repr(generics);
-            let mut s = format!("type {name}{generics}");
-            if !bounds.is_empty() {
-                s += &format!(": {}", plus_separated(bounds));
-            }
-            if let Some(default) = default {
-                s += &format!(" = {}", default.to_repr());
-            }
-            s += &format!("{where_clause};");
-
-            Some(CrateRepr::AssocType(s))
+            let signature = assoc_type_signature(ctx, &name, generics, bounds, default.as_ref());
+            Some(CrateRepr::AssocType(signature.format(ctx.max_width)))
         }
     }
 }
 
 impl ToRepr for Visibility {
-    fn to_repr(&self) -> String {
+    fn to_repr(&self, _ctx: &Context) -> String {
         match self {
             Visibility::Public => "pub".to_string(),
             Visibility::Default => "".to_string(),
             Visibility::Crate => "pub(crate)".to_string(),
-            Visibility::Restricted { parent, path } => format!("pub(in {path})"),
+            Visibility::Restricted { path, .. } => format!("pub(in {path})"),
         }
     }
 }
 
 impl ToRepr for Type {
-    fn to_repr(&self) -> String {
+    fn to_repr(&self, ctx: &Context) -> String {
         match self {
-            Type::ResolvedPath(path) => path.to_repr(),
-            Type::DynTrait(dyn_trait) => dyn_trait.to_repr(),
+            Type::ResolvedPath(path) => path.to_repr(ctx),
+            Type::DynTrait(dyn_trait) => dyn_trait.to_repr(ctx),
             Type::Generic(generic) => generic.to_string(),
             Type::Primitive(val) => val.clone(),
             Type::FunctionPointer(_) => todo!(),
             Type::Tuple(_) => todo!(),
-            Type::Slice(slice) => format!("[{}]", slice.to_repr()),
+            Type::Slice(slice) => format!("[{}]", slice.to_repr(ctx)),
             Type::Array { type_, len } => {
-                format!("[{}; {len}]", type_.to_repr())
+                format!("[{}; {len}]", type_.to_repr(ctx))
             }
-            Type::Pat {
-                type_,
-                __pat_unstable_do_not_use,
-            } => todo!(),
+            Type::Pat { .. } => todo!(),
             Type::ImplTrait(bounds) => {
-                let bounds: Vec<_> = bounds.iter().map(|b| b.to_repr()).collect();
+                let bounds: Vec<_> = bounds.iter().map(|b| b.to_repr(ctx)).collect();
 
-                format!("impl {}", plus_separated(&bounds))
+                format!("impl {}", plus_separated(ctx, &bounds))
             }
             Type::Infer => todo!(),
             Type::RawPointer { mutable, type_ } => {
                 let mutability = if *mutable { "*mut" } else { "*const" };
-                format!("{mutability} {}", type_.to_repr())
+                format!("{mutability} {}", pointee_repr(ctx, type_))
             }
             Type::BorrowedRef {
                 lifetime,
@@ -373,7 +998,7 @@ impl ToRepr for Type {
                 } else {
                     "".to_string()
                 };
-                format!("&{lifetime}{mutable}{}", type_.to_repr())
+                format!("&{lifetime}{mutable}{}", pointee_repr(ctx, type_))
             }
             Type::QualifiedPath {
                 name,
@@ -381,14 +1006,14 @@ impl ToRepr for Type {
                 self_type,
                 trait_,
             } => {
-                let mut s = self_type.to_repr();
+                let mut s = self_type.to_repr(ctx);
                 if let Some(trait_) = trait_ {
-                    let trait_ = trait_.to_repr();
+                    let trait_ = trait_.to_repr(ctx);
                     if !trait_.is_empty() {
                         s = format!("<{s} as {trait_}>");
                     }
                 }
-                let args = args.to_repr();
+                let args = args.to_repr(ctx);
                 format!("{s}::{name}{args}")
             }
         }
@@ -396,42 +1021,58 @@ impl ToRepr for Type {
 }
 
 impl ToRepr for DynTrait {
-    fn to_repr(&self) -> String {
-        let mut s = plus_separated(&self.traits);
-        let mut num_items = self.traits.len();
+    fn to_repr(&self, ctx: &Context) -> String {
+        let mut s = plus_separated(ctx, &self.traits);
         if let Some(lifetime) = &self.lifetime {
-            num_items += 1;
             s += &format!(" + {lifetime}");
         }
-        // dyn trait args need parenthesis if there's > 1 value
-        if num_items > 1 {
-            s = format!("({s})");
-        }
         format!("dyn {s}")
     }
 }
 
+// `&dyn A + B` and `&impl A + B` don't parse, the pointee needs parenthesis if there's > 1 bound
+fn pointee_repr(ctx: &Context, type_: &Type) -> String {
+    let num_bounds = match type_ {
+        Type::DynTrait(dyn_trait) => dyn_trait.traits.len() + dyn_trait.lifetime.iter().count(),
+        Type::ImplTrait(bounds) => bounds.len(),
+        _ => 0,
+    };
+    if num_bounds > 1 {
+        format!("({})", type_.to_repr(ctx))
+    } else {
+        type_.to_repr(ctx)
+    }
+}
+
 impl ToRepr for PolyTrait {
-    fn to_repr(&self) -> String {
-        let mut s = self.trait_.to_repr();
-        let generics = comma_separated(&self.generic_params);
-        if !generics.is_empty() {
-            s = format!("{generics} {s}");
-        }
-        s
+    fn to_repr(&self, ctx: &Context) -> String {
+        format!(
+            "{}{}",
+            higher_ranked_repr(ctx, &self.generic_params),
+            self.trait_.to_repr(ctx)
+        )
+    }
+}
+
+fn higher_ranked_repr(ctx: &Context, generic_params: &[GenericParamDef]) -> String {
+    let generic_params = comma_separated(ctx, generic_params);
+    if generic_params.is_empty() {
+        "".to_string()
+    } else {
+        format!("for<{generic_params}> ")
     }
 }
 
 impl ToRepr for GenericParamDef {
-    fn to_repr(&self) -> String {
+    fn to_repr(&self, ctx: &Context) -> String {
         let kind = match &self.kind {
             GenericParamDefKind::Lifetime { outlives } => {
                 // let mut s = self.name.to_string();
                 if outlives.is_empty() {
                     "".to_string()
                 } else {
-                    // format!(": {}", plus_separated(outlives))
-                    plus_separated(outlives)
+                    // format!(": {}", plus_separated(ctx, outlives))
+                    plus_separated(ctx, outlives)
                 }
             }
             GenericParamDefKind::Type {
@@ -442,18 +1083,18 @@ impl ToRepr for GenericParamDef {
                 if *synthetic {
                     return "".to_owned();
                 }
-                let mut s = plus_separated(bounds);
+                let mut s = plus_separated(ctx, bounds);
                 if let Some(default) = default {
-                    s += &format!(" = {}", default.to_repr());
+                    s += &format!(" = {}", default.to_repr(ctx));
                 }
                 s
             }
             GenericParamDefKind::Const { type_, default } => {
-                let mut s = format!("const {}", type_.to_repr());
+                let mut s = format!("const {}: {}", self.name, type_.to_repr(ctx));
                 if let Some(default) = default {
                     s += &format!(" = {default}");
                 }
-                s
+                return s;
             }
         };
         let mut s = self.name.clone();
@@ -465,90 +1106,234 @@ impl ToRepr for GenericParamDef {
 }
 
 impl ToRepr for WherePredicate {
-    fn to_repr(&self) -> String {
+    fn to_repr(&self, ctx: &Context) -> String {
         match self {
             WherePredicate::BoundPredicate {
                 type_,
                 bounds,
                 generic_params,
             } => {
-                let mut s = "".to_string();
-                let type_ = type_.to_repr();
-                let bounds = plus_separated(bounds);
-                if !generic_params.is_empty() {
-                    let generic_params = comma_separated(generic_params);
-                    s += &format!("for <{generic_params}>");
-                }
-                format!("{s}{type_}: {bounds}")
+                let type_ = type_.to_repr(ctx);
+                let bounds = plus_separated(ctx, bounds);
+                format!(
+                    "{}{type_}: {bounds}",
+                    higher_ranked_repr(ctx, generic_params)
+                )
             }
             WherePredicate::LifetimePredicate { lifetime, outlives } => {
-                let outlives = plus_separated(outlives);
-                format!("{lifetime} {outlives}")
+                let outlives = plus_separated(ctx, outlives);
+                format!("{lifetime}: {outlives}")
             }
             WherePredicate::EqPredicate { lhs, rhs } => {
-                format!("{} = {}", lhs.to_repr(), rhs.to_repr())
+                format!("{} = {}", lhs.to_repr(ctx), rhs.to_repr(ctx))
             }
         }
     }
 }
 
 impl ToRepr for GenericBound {
-    fn to_repr(&self) -> String {
+    fn to_repr(&self, ctx: &Context) -> String {
         match self {
             GenericBound::TraitBound {
                 trait_,
                 generic_params,
                 modifier,
             } => {
-                let trait_ = trait_.to_repr();
-                let generic_params = if !generic_params.is_empty() {
-                    format!(
-                        "<{}>",
-                        comma_separated(
-                            &generic_params
-                                .iter()
-                                .map(|p| p.to_repr())
-                                .collect::<Vec<_>>()
-                        )
-                    )
-                } else {
-                    "".to_string()
-                };
-                format!("{trait_}{generic_params}{}", modifier.to_repr())
+                format!(
+                    "{}{}{}",
+                    modifier.to_repr(ctx),
+                    higher_ranked_repr(ctx, generic_params),
+                    trait_.to_repr(ctx)
+                )
             }
-            GenericBound::Outlives(lifetime) => format!("'{lifetime}"),
+            GenericBound::Outlives(lifetime) => lifetime.to_owned(),
             GenericBound::Use(use_) => {
-                let use_ = comma_separated(use_);
+                let use_ = comma_separated(ctx, use_);
                 format!("use<{use_}>")
             }
         }
     }
 }
 
-fn generics_repr(generics: &Generics) -> (String, String) {
-    let mut generic_params = comma_separated(&generics.params);
-    if !generic_params.is_empty() {
-        generic_params = format!("<{generic_params}>");
+fn fn_signature(ctx: &Context, item: &Item, func: &Function, end: SignatureEnd) -> Signature {
+    let name = item.name.as_ref().unwrap();
+    let inputs: Vec<_> = func
+        .decl
+        .inputs
+        .iter()
+        .map(|(name, ty)| input_repr(ctx, name, ty))
+        .collect();
+    let const_ = if func.header.const_ { "const " } else { "" };
+    let async_ = if func.header.async_ { "async " } else { "" };
+    let unsafe_ = if func.header.unsafe_ { "unsafe " } else { "" };
+    let (generics, where_predicates) = generics_lists(ctx, &func.generics);
+    let output = func
+        .decl
+        .output
+        .as_ref()
+        .map(|o| format!(" -> {}", o.to_repr(ctx)))
+        .unwrap_or_default();
+    let mut vis = item.visibility.to_repr(ctx);
+    if !vis.is_empty() {
+        vis += " ";
     }
-    let mut where_predicates = comma_separated(&generics.where_predicates);
-    if !where_predicates.is_empty() {
-        where_predicates = format!(" where {where_predicates}");
+    Signature {
+        head: format!("{vis}{const_}{unsafe_}{async_}fn {name}"),
+        generics,
+        args: Some(inputs),
+        tail: output,
+        where_predicates,
+        end,
     }
+}
+
+fn assoc_type_signature(
+    ctx: &Context,
+    name: &str,
+    generics: &Generics,
+    bounds: &[GenericBound],
+    default: Option<&Type>,
+) -> Signature {
+    let (generics, where_predicates) = generics_lists(ctx, generics);
+    let mut tail = String::new();
+    if !bounds.is_empty() {
+        tail += &format!(": {}", plus_separated(ctx, bounds));
+    }
+    if let Some(default) = default {
+        tail += &format!(" = {}", default.to_repr(ctx));
+    }
+    Signature {
+        head: format!("type {name}"),
+        generics,
+        args: None,
+        tail,
+        where_predicates,
+        end: SignatureEnd::Semicolon,
+    }
+}
+
+fn input_repr(ctx: &Context, name: &str, ty: &Type) -> String {
+    let ty = ty.to_repr(ctx);
+    if name == "self" && ty == "&Self" {
+        "&self".to_string()
+    } else if name == "self" && ty == "Self" {
+        "self".to_string()
+    } else {
+        format!("{name}: {ty}")
+    }
+}
+
+/// The signature on one line with `_` for every argument name but `self`, and the generic params
+/// of `func` numbered after `outer_generics`
+fn normalized_fn_signature(
+    ctx: &Context,
+    item: &Item,
+    func: &Function,
+    outer_generics: &[String],
+) -> String {
+    let mut signature = fn_signature(ctx, item, func, SignatureEnd::None);
+    let inputs = func.decl.inputs.iter().map(|(name, ty)| {
+        if name == "self" {
+            input_repr(ctx, name, ty)
+        } else {
+            input_repr(ctx, "_", ty)
+        }
+    });
+    signature.args = Some(inputs.collect());
+    let mut names = outer_generics.to_vec();
+    names.extend(generic_names(&func.generics));
+    number_generics(&signature.format(usize::MAX), &names)
+}
+
+fn generic_names(generics: &Generics) -> Vec<String> {
+    generics
+        .params
+        .iter()
+        .filter(|param| {
+            !matches!(
+                param.kind,
+                GenericParamDefKind::Type {
+                    synthetic: true,
+                    ..
+                }
+            )
+        })
+        .map(|param| param.name.clone())
+        .collect()
+}
+
+fn generic_params(ctx: &Context, generics: &Generics) -> Vec<GenericParamRepr> {
+    generics
+        .params
+        .iter()
+        .filter_map(|param| {
+            let has_default = match &param.kind {
+                GenericParamDefKind::Lifetime { .. } => false,
+                GenericParamDefKind::Type {
+                    synthetic: true, ..
+                } => return None,
+                GenericParamDefKind::Type { default, .. } => default.is_some(),
+                GenericParamDefKind::Const { default, .. } => default.is_some(),
+            };
+            Some(GenericParamRepr {
+                name: param.name.clone(),
+                repr: param.to_repr(ctx),
+                has_default,
+            })
+        })
+        .collect()
+}
+
+fn generics_lists(ctx: &Context, generics: &Generics) -> (Vec<String>, Vec<String>) {
+    let generic_params = generics
+        .params
+        .iter()
+        .map(|param| param.to_repr(ctx))
+        .filter(|param| !param.is_empty())
+        .collect();
+    // Synthetic params are rendered inline as `impl Trait`, so predicates on them must be hidden
+    // too
+    let where_predicates = generics
+        .where_predicates
+        .iter()
+        .filter(|predicate| !is_synthetic_predicate(predicate, &generics.params))
+        .map(|predicate| predicate.to_repr(ctx))
+        .collect();
     (generic_params, where_predicates)
 }
 
+fn is_synthetic_predicate(predicate: &WherePredicate, params: &[GenericParamDef]) -> bool {
+    let WherePredicate::BoundPredicate {
+        type_: Type::Generic(name),
+        ..
+    } = predicate
+    else {
+        return false;
+    };
+    params.iter().any(|param| {
+        param.name == *name
+            && matches!(
+                param.kind,
+                GenericParamDefKind::Type {
+                    synthetic: true,
+                    ..
+                }
+            )
+    })
+}
+
 impl ToRepr for TraitBoundModifier {
-    fn to_repr(&self) -> String {
+    fn to_repr(&self, _ctx: &Context) -> String {
         match self {
             TraitBoundModifier::None => "".to_string(),
             TraitBoundModifier::Maybe => "?".to_string(),
-            TraitBoundModifier::MaybeConst => "?const".to_string(),
+            TraitBoundModifier::MaybeConst => "~const ".to_string(),
         }
     }
 }
 
 impl ToRepr for String {
-    fn to_repr(&self) -> String {
+    fn to_repr(&self, _ctx: &Context) -> String {
         self.clone()
     }
 }
@@ -557,32 +1342,43 @@ impl<T> ToRepr for Box<T>
 where
     T: ToRepr,
 {
-    fn to_repr(&self) -> String {
-        (**self).to_repr()
+    fn to_repr(&self, ctx: &Context) -> String {
+        (**self).to_repr(ctx)
+    }
+}
+
+impl<T> ToRepr for &T
+where
+    T: ToRepr,
+{
+    fn to_repr(&self, ctx: &Context) -> String {
+        (**self).to_repr(ctx)
     }
 }
 
 impl ToRepr for Path {
-    fn to_repr(&self) -> String {
-        let mut s = self.name.to_string();
-        s += &self.args.to_repr();
+    fn to_repr(&self, ctx: &Context) -> String {
+        let mut s = ctx
+            .workspace_path(&self.id)
+            .unwrap_or_else(|| self.name.to_string());
+        s += &self.args.to_repr(ctx);
         s
     }
 }
 
 impl ToRepr for GenericArgs {
-    fn to_repr(&self) -> String {
+    fn to_repr(&self, ctx: &Context) -> String {
         match self {
             GenericArgs::AngleBracketed { args, bindings } => {
                 let mut s = "".to_string();
                 if !args.is_empty() {
-                    s += &comma_separated(args);
+                    s += &comma_separated(ctx, args);
                 }
                 if !bindings.is_empty() {
                     if !s.is_empty() {
                         s += ", ";
                     }
-                    s += &comma_separated(bindings);
+                    s += &comma_separated(ctx, bindings);
                 }
                 if !s.is_empty() {
                     format!("<{s}>")
@@ -591,9 +1387,9 @@ impl ToRepr for GenericArgs {
                 }
             }
             GenericArgs::Parenthesized { inputs, output } => {
-                let mut s = format!("({})", comma_separated(inputs));
+                let mut s = format!("({})", comma_separated(ctx, inputs));
                 if let Some(output) = output {
-                    s += &format!(" -> {}", output.to_repr());
+                    s += &format!(" -> {}", output.to_repr(ctx));
                 }
                 s
             }
@@ -602,73 +1398,70 @@ impl ToRepr for GenericArgs {
 }
 
 impl ToRepr for GenericArg {
-    fn to_repr(&self) -> String {
+    fn to_repr(&self, ctx: &Context) -> String {
         match self {
             GenericArg::Lifetime(lifetime) => lifetime.to_owned(),
-            GenericArg::Type(ty) => ty.to_repr(),
-            GenericArg::Const(constant) => constant.to_repr(),
+            GenericArg::Type(ty) => ty.to_repr(ctx),
+            GenericArg::Const(constant) => constant.to_repr(ctx),
             GenericArg::Infer => "_".to_string(),
         }
     }
 }
 
-fn comma_separated<T>(t: &[T]) -> String
+fn comma_separated<T>(ctx: &Context, t: &[T]) -> String
 where
     T: ToRepr,
 {
     t.iter()
-        .map(|g| g.to_repr())
+        .map(|g| g.to_repr(ctx))
         .filter(|g| !g.is_empty())
         .collect::<Vec<_>>()
         .join(", ")
 }
 
-fn plus_separated<T>(t: &[T]) -> String
+fn plus_separated<T>(ctx: &Context, t: &[T]) -> String
 where
     T: ToRepr,
 {
     t.iter()
-        .map(|g| g.to_repr())
+        .map(|g| g.to_repr(ctx))
         .filter(|g| !g.is_empty())
         .collect::<Vec<_>>()
         .join(" + ")
 }
 
 impl ToRepr for Constant {
-    fn to_repr(&self) -> String {
+    fn to_repr(&self, _ctx: &Context) -> String {
         todo!()
     }
 }
 
 impl ToRepr for TypeBinding {
-    fn to_repr(&self) -> String {
-        let mut s = self.name.clone();
-        let args = self.args.to_repr();
-        if !args.is_empty() {
-            s += &format!(" {args}");
-        }
-        let binding = self.binding.to_repr();
-        if !binding.is_empty() {
-            s += &format!(" {binding}");
-        }
-        s
+    fn to_repr(&self, ctx: &Context) -> String {
+        // `Item<'a> = T` or `Item: Bound`
+        format!(
+            "{}{}{}",
+            self.name,
+            self.args.to_repr(ctx),
+            self.binding.to_repr(ctx)
+        )
     }
 }
 
 impl ToRepr for TypeBindingKind {
-    fn to_repr(&self) -> String {
+    fn to_repr(&self, ctx: &Context) -> String {
         match self {
-            TypeBindingKind::Equality(term) => format!("= {}", term.to_repr()),
-            TypeBindingKind::Constraint(bounds) => plus_separated(bounds),
+            TypeBindingKind::Equality(term) => format!(" = {}", term.to_repr(ctx)),
+            TypeBindingKind::Constraint(bounds) => format!(": {}", plus_separated(ctx, bounds)),
         }
     }
 }
 
 impl ToRepr for Term {
-    fn to_repr(&self) -> String {
+    fn to_repr(&self, ctx: &Context) -> String {
         match self {
-            Term::Type(ty) => ty.to_repr(),
-            Term::Constant(constant) => constant.to_repr(),
+            Term::Type(ty) => ty.to_repr(ctx),
+            Term::Constant(constant) => constant.to_repr(ctx),
         }
     }
 }
@@ -677,9 +1470,9 @@ impl<T> ToRepr for Option<T>
 where
     T: ToRepr,
 {
-    fn to_repr(&self) -> String {
+    fn to_repr(&self, ctx: &Context) -> String {
         match self {
-            Some(t) => t.to_repr(),
+            Some(t) => t.to_repr(ctx),
             None => "".to_string(),
         }
     }
@@ -0,0 +1,194 @@
+use rustdoc_types::{
+    Function, GenericArg, GenericArgs, GenericBound, GenericParamDefKind, Generics, ItemEnum, Path,
+    Struct, StructKind, Term, Type, TypeBindingKind, WherePredicate,
+};
+
+use crate::{Context, TypeRef};
+
+/// Collects the resolved paths of every type and trait named in a signature
+pub(crate) trait Mentions {
+    fn mentions<'a>(&'a self, paths: &mut Vec<&'a Path>);
+}
+
+impl Mentions for Type {
+    fn mentions<'a>(&'a self, paths: &mut Vec<&'a Path>) {
+        match self {
+            Type::ResolvedPath(path) => path.mentions(paths),
+            Type::DynTrait(dyn_trait) => {
+                for poly_trait in &dyn_trait.traits {
+                    poly_trait.trait_.mentions(paths);
+                }
+            }
+            Type::FunctionPointer(pointer) => {
+                for (_, type_) in &pointer.decl.inputs {
+                    type_.mentions(paths);
+                }
+                if let Some(output) = &pointer.decl.output {
+                    output.mentions(paths);
+                }
+            }
+            Type::Tuple(types) => {
+                for type_ in types {
+                    type_.mentions(paths);
+                }
+            }
+            Type::Slice(type_)
+            | Type::Array { type_, .. }
+            | Type::Pat { type_, .. }
+            | Type::RawPointer { type_, .. }
+            | Type::BorrowedRef { type_, .. } => type_.mentions(paths),
+            Type::ImplTrait(bounds) => bounds.mentions(paths),
+            Type::QualifiedPath {
+                args,
+                self_type,
+                trait_,
+                ..
+            } => {
+                self_type.mentions(paths);
+                if let Some(trait_) = trait_ {
+                    trait_.mentions(paths);
+                }
+                args.mentions(paths);
+            }
+            Type::Generic(_) | Type::Primitive(_) | Type::Infer => {}
+        }
+    }
+}
+
+impl Mentions for Path {
+    fn mentions<'a>(&'a self, paths: &mut Vec<&'a Path>) {
+        paths.push(self);
+        if let Some(args) = &self.args {
+            args.mentions(paths);
+        }
+    }
+}
+
+impl Mentions for GenericArgs {
+    fn mentions<'a>(&'a self, paths: &mut Vec<&'a Path>) {
+        match self {
+            GenericArgs::AngleBracketed { args, bindings } => {
+                for arg in args {
+                    if let GenericArg::Type(type_) = arg {
+                        type_.mentions(paths);
+                    }
+                }
+                for binding in bindings {
+                    binding.args.mentions(paths);
+                    match &binding.binding {
+                        TypeBindingKind::Equality(Term::Type(type_)) => type_.mentions(paths),
+                        TypeBindingKind::Equality(Term::Constant(_)) => {}
+                        TypeBindingKind::Constraint(bounds) => bounds.mentions(paths),
+                    }
+                }
+            }
+            GenericArgs::Parenthesized { inputs, output } => {
+                for input in inputs {
+                    input.mentions(paths);
+                }
+                if let Some(output) = output {
+                    output.mentions(paths);
+                }
+            }
+        }
+    }
+}
+
+impl Mentions for [GenericBound] {
+    fn mentions<'a>(&'a self, paths: &mut Vec<&'a Path>) {
+        for bound in self {
+            if let GenericBound::TraitBound { trait_, .. } = bound {
+                trait_.mentions(paths);
+            }
+        }
+    }
+}
+
+impl Mentions for Generics {
+    fn mentions<'a>(&'a self, paths: &mut Vec<&'a Path>) {
+        for param in &self.params {
+            match &param.kind {
+                GenericParamDefKind::Type {
+                    bounds, default, ..
+                } => {
+                    bounds.mentions(paths);
+                    if let Some(default) = default {
+                        default.mentions(paths);
+                    }
+                }
+                GenericParamDefKind::Const { type_, .. } => type_.mentions(paths),
+                GenericParamDefKind::Lifetime { .. } => {}
+            }
+        }
+        for predicate in &self.where_predicates {
+            match predicate {
+                WherePredicate::BoundPredicate { type_, bounds, .. } => {
+                    type_.mentions(paths);
+                    bounds.mentions(paths);
+                }
+                WherePredicate::EqPredicate { lhs, rhs } => {
+                    lhs.mentions(paths);
+                    if let Term::Type(rhs) = rhs {
+                        rhs.mentions(paths);
+                    }
+                }
+                WherePredicate::LifetimePredicate { .. } => {}
+            }
+        }
+    }
+}
+
+/// Resolves the collected paths to the full path of the item they point to, each item is only
+/// included once
+pub(crate) fn type_refs(ctx: &Context, paths: Vec<&Path>) -> Vec<TypeRef> {
+    let mut type_refs: Vec<TypeRef> = Vec::new();
+    for path in paths {
+        if type_refs.iter().any(|type_ref| type_ref.id == path.id) {
+            continue;
+        }
+        // Items that rustdoc doesn't have a summary for are kept with the path as written
+        let full_path = match ctx.crate_docs.paths.get(&path.id) {
+            Some(summary) => ctx
+                .public_paths
+                .get(&summary.path)
+                .unwrap_or(&summary.path)
+                .clone(),
+            None => path.name.split("::").map(str::to_string).collect(),
+        };
+        type_refs.push(TypeRef {
+            id: path.id.clone(),
+            path: full_path,
+        });
+    }
+    type_refs
+}
+
+/// The types mentioned in the arguments and generics, and in the return type
+pub(crate) fn fn_types(ctx: &Context, func: &Function) -> (Vec<TypeRef>, Vec<TypeRef>) {
+    let mut inputs = Vec::new();
+    for (_, type_) in &func.decl.inputs {
+        type_.mentions(&mut inputs);
+    }
+    func.generics.mentions(&mut inputs);
+    let mut output = Vec::new();
+    if let Some(type_) = &func.decl.output {
+        type_.mentions(&mut output);
+    }
+    (type_refs(ctx, inputs), type_refs(ctx, output))
+}
+
+/// The types mentioned in the visible fields of a struct
+pub(crate) fn field_types(ctx: &Context, struct_: &Struct) -> Vec<TypeRef> {
+    let ids: Vec<_> = match &struct_.kind {
+        StructKind::Unit => Vec::new(),
+        StructKind::Tuple(ids) => ids.iter().flatten().collect(),
+        StructKind::Plain { fields, .. } => fields.iter().collect(),
+    };
+    let mut paths = Vec::new();
+    for id in ids {
+        if let ItemEnum::StructField(type_) = &ctx.crate_docs.index[id].inner {
+            type_.mentions(&mut paths);
+        }
+    }
+    type_refs(ctx, paths)
+}
@@ -0,0 +1,349 @@
+use crate::{Id, ItemKind, ItemRepr, ModuleRepr, TypeRef};
+
+/// Finds items in the model by path, kind and the types named in their signatures.
+///
+/// Paths are matched with globs over `::` separated segments, where `*` matches any characters
+/// within a segment and `**` matches any number of whole segments. Item paths start with the crate
+/// name, type paths are the full path where the type is defined, e.g. `std::io::error::Error`, so
+/// `std::io::**::Error` or `**::io::**` are usually more robust than exact paths.
+///
+/// All filters must match. For example, every function returning a `Result` with an `io::Error`:
+///
+/// ```no_run
+/// use rustdoc_code_formatter::{ItemKind, Query};
+///
+/// let modules = rustdoc_code_formatter::build("Cargo.toml");
+/// let query = Query::new()
+///     .kind(ItemKind::Fn)
+///     .returns("**::Result")
+///     .returns("std::io::**::Error");
+/// for item in query.run(&modules[0]) {
+///     println!("{}", item.path().join("::"));
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    path: Option<String>,
+    kinds: Vec<ItemKind>,
+    mentions: Vec<TypePattern>,
+    returns: Vec<TypePattern>,
+}
+
+#[derive(Debug, Clone)]
+enum TypePattern {
+    Path(String),
+    Id(Id),
+}
+
+impl TypePattern {
+    fn matches(&self, type_ref: &TypeRef) -> bool {
+        match self {
+            TypePattern::Path(pattern) => path_matches(pattern, &type_ref.path),
+            TypePattern::Id(id) => &type_ref.id == id,
+        }
+    }
+}
+
+impl Query {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only items whose path matches the glob, e.g. `my_crate::net::**`
+    pub fn path(mut self, pattern: impl Into<String>) -> Self {
+        self.path = Some(pattern.into());
+        self
+    }
+
+    /// Only items of this kind, calling it again allows more kinds
+    pub fn kind(mut self, kind: ItemKind) -> Self {
+        self.kinds.push(kind);
+        self
+    }
+
+    /// Only items that name a type or trait matching the glob anywhere in their signature
+    pub fn mentions(mut self, pattern: impl Into<String>) -> Self {
+        self.mentions.push(TypePattern::Path(pattern.into()));
+        self
+    }
+
+    /// Like [`Self::mentions`], but matches the rustdoc id of the type
+    pub fn mentions_id(mut self, id: Id) -> Self {
+        self.mentions.push(TypePattern::Id(id));
+        self
+    }
+
+    /// Only functions and trait items that name a type or trait matching the glob in their return
+    /// type, or in the type of an associated constant or type
+    pub fn returns(mut self, pattern: impl Into<String>) -> Self {
+        self.returns.push(TypePattern::Path(pattern.into()));
+        self
+    }
+
+    /// Like [`Self::returns`], but matches the rustdoc id of the type
+    pub fn returns_id(mut self, id: Id) -> Self {
+        self.returns.push(TypePattern::Id(id));
+        self
+    }
+
+    pub fn matches(&self, item: ItemRepr) -> bool {
+        if let Some(pattern) = &self.path {
+            if !path_matches(pattern, item.path()) {
+                return false;
+            }
+        }
+        if !self.kinds.is_empty() && !self.kinds.contains(&item.kind()) {
+            return false;
+        }
+
+        let (input_types, output_types): (&[TypeRef], &[TypeRef]) = match item {
+            ItemRepr::Fn(func) => (&func.input_types, &func.output_types),
+            ItemRepr::Struct(struct_) => (&struct_.field_types, &[]),
+            ItemRepr::TraitItem(item) => (&item.input_types, &item.output_types),
+            ItemRepr::Module(_) | ItemRepr::Trait(_) => (&[], &[]),
+        };
+        let all_types = || input_types.iter().chain(output_types);
+        self.mentions
+            .iter()
+            .all(|pattern| all_types().any(|type_ref| pattern.matches(type_ref)))
+            && self.returns.iter().all(|pattern| {
+                output_types
+                    .iter()
+                    .any(|type_ref| pattern.matches(type_ref))
+            })
+    }
+
+    /// Every item in `module` and its submodules that matches, in the order of
+    /// [`ModuleRepr::items`]
+    pub fn run<'a>(&'a self, module: &'a ModuleRepr) -> impl Iterator<Item = ItemRepr<'a>> + 'a {
+        module.items().filter(|item| self.matches(*item))
+    }
+}
+
+fn path_matches(pattern: &str, path: &[String]) -> bool {
+    let pattern: Vec<_> = pattern.split("::").collect();
+    segments_match(&pattern, path)
+}
+
+fn segments_match(pattern: &[&str], path: &[String]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| segments_match(rest, &path[skip..])),
+        Some((segment, rest)) => match path.split_first() {
+            Some((first, path_rest)) => {
+                segment_matches(segment, first) && segments_match(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+// `*` matches any run of characters, including none
+fn segment_matches(pattern: &str, segment: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return pattern == segment;
+    };
+    let Some(segment) = segment.strip_prefix(prefix) else {
+        return false;
+    };
+    (0..=segment.len())
+        .filter(|i| segment.is_char_boundary(*i))
+        .any(|i| segment_matches(rest, &segment[i..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Query, path_matches, segment_matches};
+    use crate::{FnRepr, Id, ItemKind, ItemRepr, ModuleRepr, StructRepr, TypeRef};
+
+    fn path(path: &str) -> Vec<String> {
+        if path.is_empty() {
+            return Vec::new();
+        }
+        path.split("::").map(str::to_string).collect()
+    }
+
+    fn type_ref(id: &str, type_path: &str) -> TypeRef {
+        TypeRef {
+            id: Id(id.to_string()),
+            path: path(type_path),
+        }
+    }
+
+    fn function(name: &str, inputs: Vec<TypeRef>, outputs: Vec<TypeRef>) -> FnRepr {
+        FnRepr {
+            id: Id(name.to_string()),
+            name: name.to_string(),
+            path: path(&format!("api::{name}")),
+            span: None,
+            repr: String::new(),
+            signature: String::new(),
+            is_unsafe: false,
+            generics: Vec::new(),
+            input_types: inputs,
+            output_types: outputs,
+        }
+    }
+
+    fn struct_(name: &str, field_types: Vec<TypeRef>) -> StructRepr {
+        StructRepr {
+            id: Id(name.to_string()),
+            name: name.to_string(),
+            path: path(&format!("api::{name}")),
+            span: None,
+            repr: String::new(),
+            signature: String::new(),
+            generics: Vec::new(),
+            fields: Vec::new(),
+            has_private_fields: false,
+            non_exhaustive: false,
+            auto_traits: Vec::new(),
+            field_types,
+        }
+    }
+
+    fn names(query: &Query, module: &ModuleRepr) -> Vec<String> {
+        query
+            .run(module)
+            .map(|item| item.path().join("::"))
+            .collect()
+    }
+
+    fn module() -> ModuleRepr {
+        let io_error = || type_ref("1", "std::io::error::Error");
+        let result = || type_ref("2", "core::result::Result");
+        ModuleRepr {
+            id: Id("0".to_string()),
+            name: "api".to_string(),
+            path: path("api"),
+            span: None,
+            functions: vec![
+                function("read", Vec::new(), vec![result(), io_error()]),
+                function("log", vec![io_error()], Vec::new()),
+            ],
+            structs: vec![struct_("Wrapper", vec![io_error()])],
+            traits: Vec::new(),
+            modules: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn double_star_matches_any_segments() {
+        let path = path("std::io::error::Error");
+        // Leading
+        assert!(path_matches("**::Error", &path));
+        assert!(!path_matches("**::error", &path));
+        // Middle, including no segments at all
+        assert!(path_matches("std::**::Error", &path));
+        assert!(path_matches("std::io::**::error::Error", &path));
+        assert!(!path_matches("core::**::Error", &path));
+        // Trailing
+        assert!(path_matches("std::io::**", &path));
+        assert!(path_matches("std::io::error::Error::**", &path));
+        assert!(path_matches("**", &path));
+    }
+
+    #[test]
+    fn empty_paths() {
+        assert!(path_matches("**", &[]));
+        assert!(!path_matches("std", &[]));
+        assert!(!path_matches("*", &[]));
+        assert!(!path_matches("", &path("std")));
+    }
+
+    #[test]
+    fn star_matches_within_a_segment() {
+        assert!(segment_matches("*", ""));
+        assert!(segment_matches("Err*", "Error"));
+        assert!(segment_matches("*or", "Error"));
+        assert!(segment_matches("E*r*r", "Error"));
+        assert!(segment_matches("Ér*", "Érror"));
+        assert!(!segment_matches("Err*x", "Error"));
+        assert!(path_matches(
+            "std::*::*::Err*",
+            &path("std::io::error::Error")
+        ));
+        // `*` doesn't cross segments
+        assert!(!path_matches(
+            "std::*::Error",
+            &path("std::io::error::Error")
+        ));
+    }
+
+    #[test]
+    fn path_filter() {
+        let module = module();
+        assert_eq!(names(&Query::new().path("api::r*"), &module), ["api::read"]);
+        assert_eq!(
+            names(&Query::new().path("**::Wrapper"), &module),
+            ["api::Wrapper"]
+        );
+    }
+
+    #[test]
+    fn kind_filter() {
+        let module = module();
+        assert_eq!(
+            names(&Query::new().kind(ItemKind::Struct), &module),
+            ["api::Wrapper"]
+        );
+        assert_eq!(
+            names(
+                &Query::new().kind(ItemKind::Module).kind(ItemKind::Struct),
+                &module
+            ),
+            ["api", "api::Wrapper"]
+        );
+    }
+
+    #[test]
+    fn mentions_filter() {
+        let module = module();
+        // Inputs, outputs and fields
+        assert_eq!(
+            names(&Query::new().mentions("std::io::**::Error"), &module),
+            ["api::read", "api::log", "api::Wrapper"]
+        );
+        assert_eq!(
+            names(&Query::new().mentions_id(Id("2".to_string())), &module),
+            ["api::read"]
+        );
+        // Every pattern has to match
+        assert_eq!(
+            names(
+                &Query::new().mentions("**::Result").mentions("**::Error"),
+                &module
+            ),
+            ["api::read"]
+        );
+    }
+
+    #[test]
+    fn returns_filter() {
+        let module = module();
+        assert_eq!(
+            names(&Query::new().returns("**::Error"), &module),
+            ["api::read"]
+        );
+        assert_eq!(
+            names(&Query::new().returns_id(Id("1".to_string())), &module),
+            ["api::read"]
+        );
+        assert!(names(&Query::new().returns("**::Missing"), &module).is_empty());
+    }
+
+    #[test]
+    fn matches_single_items() {
+        let log = function("log", vec![type_ref("1", "std::io::Error")], Vec::new());
+        assert!(
+            Query::new()
+                .mentions("std::io::Error")
+                .matches(ItemRepr::Fn(&log))
+        );
+        assert!(
+            !Query::new()
+                .returns("std::io::Error")
+                .matches(ItemRepr::Fn(&log))
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::{HashMap, HashSet};
+
+use rustdoc_types::{Crate, Id, ItemEnum, Visibility};
+
+/// Maps the path where each public item of `crate_docs` is defined to the path users can name it
+/// by. Items in private modules are only reachable through re-exports, e.g. `my_crate::Item` for
+/// an item defined in `my_crate::inner::Item`. The definition path wins if it's public itself,
+/// otherwise the shortest re-export.
+pub(crate) fn public_paths(crate_docs: &Crate) -> HashMap<Vec<String>, Vec<String>> {
+    let mut candidates: HashMap<Vec<String>, Vec<Vec<String>>> = HashMap::new();
+    let root = &crate_docs.index[&crate_docs.root];
+    let crate_name = root.name.clone().unwrap();
+    let mut visited = HashSet::new();
+    visit_module(
+        crate_docs,
+        &crate_docs.root,
+        &[crate_name],
+        &mut visited,
+        &mut candidates,
+    );
+
+    candidates
+        .into_iter()
+        .map(|(definition, mut public)| {
+            let path = if public.contains(&definition) {
+                definition.clone()
+            } else {
+                public.sort_by_key(|path| (path.len(), path.clone()));
+                public.swap_remove(0)
+            };
+            (definition, path)
+        })
+        .collect()
+}
+
+fn visit_module(
+    crate_docs: &Crate,
+    id: &Id,
+    module_path: &[String],
+    visited: &mut HashSet<(Id, Vec<String>)>,
+    candidates: &mut HashMap<Vec<String>, Vec<Vec<String>>>,
+) {
+    // Modules can re-export their ancestors
+    if !visited.insert((id.clone(), module_path.to_vec())) {
+        return;
+    }
+    let Some(ItemEnum::Module(module)) = crate_docs.index.get(id).map(|item| &item.inner) else {
+        return;
+    };
+    for id in &module.items {
+        let item = &crate_docs.index[id];
+        if item.visibility != Visibility::Public {
+            continue;
+        }
+        let (target, name, source) = match &item.inner {
+            ItemEnum::Import(import) => {
+                let Some(target) = &import.id else {
+                    continue;
+                };
+                if import.glob {
+                    visit_module(crate_docs, target, module_path, visited, candidates);
+                    continue;
+                }
+                let source = resolve_source(module_path, &import.source);
+                (target, &import.name, source)
+            }
+            _ => match &item.name {
+                Some(name) => {
+                    let mut source = module_path.to_vec();
+                    source.push(name.clone());
+                    (id, name, source)
+                }
+                None => continue,
+            },
+        };
+        let mut path = module_path.to_vec();
+        path.push(name.clone());
+        // Items of private modules aren't in `paths`, the path they're imported from is where
+        // they're defined unless it's a re-export itself
+        let definition = match crate_docs.paths.get(target) {
+            Some(summary) => summary.path.clone(),
+            None => source,
+        };
+        candidates.entry(definition).or_default().push(path.clone());
+        visit_module(crate_docs, target, &path, visited, candidates);
+    }
+}
+
+// The absolute path of `source` as written in a `use` in the module at `module_path`
+fn resolve_source(module_path: &[String], source: &str) -> Vec<String> {
+    let mut path = module_path.to_vec();
+    for (i, segment) in source.split("::").enumerate() {
+        match segment {
+            "crate" if i == 0 => path.truncate(1),
+            "self" if i == 0 => {}
+            "super" => {
+                path.pop();
+            }
+            _ => path.push(segment.to_string()),
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_source;
+
+    #[test]
+    fn resolves_source_paths() {
+        let module = ["my_crate".to_string(), "a".to_string()];
+        assert_eq!(
+            resolve_source(&module, "inner::Item"),
+            ["my_crate", "a", "inner", "Item"]
+        );
+        assert_eq!(
+            resolve_source(&module, "self::inner::Item"),
+            ["my_crate", "a", "inner", "Item"]
+        );
+        assert_eq!(
+            resolve_source(&module, "super::b::Item"),
+            ["my_crate", "b", "Item"]
+        );
+        assert_eq!(
+            resolve_source(&module, "crate::b::Item"),
+            ["my_crate", "b", "Item"]
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ModuleRepr;
+
+/// Bumped whenever a field is renamed, removed or changes meaning. Adding fields doesn't bump it,
+/// so consumers should ignore fields they don't know.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The serialized form of the model, one root module per crate.
+///
+/// Every struct is an object with the same field names as in Rust. Ids are rustdoc's item ids,
+/// paths are arrays of segments, missing spans are `null` and trait item kinds are one of `"fn"`,
+/// `"assoc_const"` or `"assoc_type"`. As JSON:
+///
+/// ```json
+/// {
+///   "schema_version": 1,
+///   "crates": [
+///     {
+///       "id": "0:0:1234",
+///       "name": "my_crate",
+///       "path": ["my_crate"],
+///       "span": { "filename": "src/lib.rs", "start_line": 1, "start_column": 1, "end_line": 9, "end_column": 2 },
+///       "functions": [{ "id": "0:3:1235", "name": "run", "path": ["my_crate", "run"], "span": null, "repr": "pub fn run() -> String", "signature": "pub fn run() -> String", "is_unsafe": false, "generics": [], "input_types": [], "output_types": [{ "id": "2:7984:249", "path": ["alloc", "string", "String"] }] }],
+///       "structs": [],
+///       "traits": [],
+///       "modules": []
+///     }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Document {
+    pub schema_version: u32,
+    pub crates: Vec<ModuleRepr>,
+}
+
+impl Document {
+    pub fn new(crates: Vec<ModuleRepr>) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            crates,
+        }
+    }
+
+    /// Whether the document was written with a schema this version can read
+    pub fn is_supported(&self) -> bool {
+        self.schema_version == SCHEMA_VERSION
+    }
+}
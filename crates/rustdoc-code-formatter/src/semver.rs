@@ -0,0 +1,827 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde_json::json;
+
+use crate::signature::number_generics;
+use crate::{
+    GenericParamRepr, ItemKind, ItemRepr, ModuleRepr, StructRepr, TraitItemRepr, TraitRepr,
+};
+
+/// How a change affects the version number according to cargo's semver rules, ordered from least
+/// to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SemverImpact {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl fmt::Display for SemverImpact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let impact = match self {
+            SemverImpact::Patch => "patch",
+            SemverImpact::Minor => "minor",
+            SemverImpact::Major => "major",
+        };
+        f.write_str(impact)
+    }
+}
+
+#[derive(Debug)]
+pub struct SemverChange {
+    pub path: Vec<String>,
+    pub kind: ItemKind,
+    pub impact: SemverImpact,
+    pub reason: String,
+}
+
+#[derive(Debug, Default)]
+pub struct SemverReport {
+    /// Sorted by path
+    pub changes: Vec<SemverChange>,
+}
+
+impl SemverReport {
+    /// The most severe impact of all changes, a release without API changes is a patch
+    pub fn impact(&self) -> SemverImpact {
+        self.changes
+            .iter()
+            .map(|change| change.impact)
+            .max()
+            .unwrap_or(SemverImpact::Patch)
+    }
+
+    /// Bumps `version` according to [`Self::impact`]. Like cargo, the leftmost non-zero component
+    /// is treated as the major version. Returns `None` if `version` isn't `major.minor.patch`.
+    pub fn next_version(&self, version: &str) -> Option<String> {
+        // Pre-release and build metadata are dropped when releasing
+        let version = version.split(['-', '+']).next()?;
+        let parts: Vec<u64> = version
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<_>>()?;
+        let [major, minor, patch] = parts[..] else {
+            return None;
+        };
+        let next = match (self.impact(), major, minor) {
+            (SemverImpact::Major, 0, 0)
+            | (SemverImpact::Minor, 0, 0)
+            | (SemverImpact::Patch, ..) => (major, minor, patch + 1),
+            (SemverImpact::Major, 0, _) => (0, minor + 1, 0),
+            (SemverImpact::Minor, 0, _) => (0, minor, patch + 1),
+            (SemverImpact::Major, ..) => (major + 1, 0, 0),
+            (SemverImpact::Minor, ..) => (major, minor + 1, 0),
+        };
+        Some(format!("{}.{}.{}", next.0, next.1, next.2))
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let changes: Vec<_> = self
+            .changes
+            .iter()
+            .map(|change| {
+                json!({
+                    "path": change.path.join("::"),
+                    "kind": change.kind.to_string(),
+                    "impact": change.impact.to_string(),
+                    "reason": change.reason,
+                })
+            })
+            .collect();
+        json!({
+            "impact": self.impact().to_string(),
+            "changes": changes,
+        })
+    }
+}
+
+/// Classifies every change between `old` and `new` using the rules from
+/// <https://doc.rust-lang.org/cargo/reference/semver.html>
+pub fn semver_check(old: &ModuleRepr, new: &ModuleRepr) -> SemverReport {
+    let old_items = items_by_key(old);
+    let new_items = items_by_key(new);
+
+    let mut report = SemverReport::default();
+    for (key, old_item) in &old_items {
+        let mut push = |impact, reason: String| {
+            report.changes.push(SemverChange {
+                path: key.0.clone(),
+                kind: key.1,
+                impact,
+                reason,
+            })
+        };
+        let Some(new_item) = new_items.get(key) else {
+            push(SemverImpact::Major, format!("{} was removed", key.1));
+            continue;
+        };
+        for (impact, reason) in compare(*old_item, *new_item) {
+            push(impact, reason);
+        }
+    }
+
+    for (key, new_item) in &new_items {
+        if old_items.contains_key(key) {
+            continue;
+        }
+        let (impact, reason) = match new_item {
+            // Implementors of a trait that already existed have to provide the new item
+            ItemRepr::TraitItem(item) if item.required && parent_trait_existed(&old_items, key) => {
+                (
+                    SemverImpact::Major,
+                    format!("required {} was added to an existing trait", key.1),
+                )
+            }
+            _ => (SemverImpact::Minor, format!("{} was added", key.1)),
+        };
+        report.changes.push(SemverChange {
+            path: key.0.clone(),
+            kind: key.1,
+            impact,
+            reason,
+        });
+    }
+    report
+        .changes
+        .sort_by(|a, b| (&a.path, a.kind).cmp(&(&b.path, b.kind)));
+    report
+}
+
+type ItemKey = (Vec<String>, ItemKind);
+
+fn items_by_key(module: &ModuleRepr) -> BTreeMap<ItemKey, ItemRepr<'_>> {
+    module
+        .items()
+        .map(|item| ((item.path().to_vec(), item.kind()), item))
+        .collect()
+}
+
+fn parent_trait_existed(old_items: &BTreeMap<ItemKey, ItemRepr>, key: &ItemKey) -> bool {
+    let parent = key.0[..key.0.len() - 1].to_vec();
+    old_items.contains_key(&(parent, ItemKind::Trait))
+}
+
+fn compare(old: ItemRepr, new: ItemRepr) -> Vec<(SemverImpact, String)> {
+    match (old, new) {
+        // Renaming arguments or generic params doesn't affect callers
+        (ItemRepr::Fn(old), ItemRepr::Fn(new)) if old.signature != new.signature => {
+            vec![(SemverImpact::Major, "signature changed".to_string())]
+        }
+        (ItemRepr::Struct(old), ItemRepr::Struct(new)) => compare_structs(old, new),
+        (ItemRepr::Trait(old), ItemRepr::Trait(new)) => compare_traits(old, new),
+        (ItemRepr::TraitItem(old), ItemRepr::TraitItem(new)) => compare_trait_items(old, new),
+        _ => Vec::new(),
+    }
+}
+
+fn compare_structs(old: &StructRepr, new: &StructRepr) -> Vec<(SemverImpact, String)> {
+    let mut changes = compare_generics(&old.generics, &new.generics);
+
+    match (old.non_exhaustive, new.non_exhaustive) {
+        (false, true) => changes.push((
+            SemverImpact::Major,
+            "`#[non_exhaustive]` was added".to_string(),
+        )),
+        (true, false) => changes.push((
+            SemverImpact::Minor,
+            "`#[non_exhaustive]` was removed".to_string(),
+        )),
+        _ => {}
+    }
+
+    for auto_trait in &old.auto_traits {
+        if !new.auto_traits.contains(auto_trait) {
+            changes.push((
+                SemverImpact::Major,
+                format!("no longer implements `{auto_trait}`"),
+            ));
+        }
+    }
+    for auto_trait in &new.auto_traits {
+        if !old.auto_traits.contains(auto_trait) {
+            changes.push((
+                SemverImpact::Minor,
+                format!("now implements `{auto_trait}`"),
+            ));
+        }
+    }
+
+    let old_fields = numbered_fields(old);
+    let new_fields = numbered_fields(new);
+    for (field, numbered) in old.fields.iter().zip(&old_fields) {
+        if !new_fields.contains(numbered) {
+            changes.push((
+                SemverImpact::Major,
+                format!("field `{field}` was removed or changed"),
+            ));
+        }
+    }
+    // Struct literals and patterns break if they can name every field
+    let exhaustive = !old.has_private_fields && !old.non_exhaustive;
+    for (field, numbered) in new.fields.iter().zip(&new_fields) {
+        if !old_fields.contains(numbered) {
+            let impact = if exhaustive {
+                SemverImpact::Major
+            } else {
+                SemverImpact::Minor
+            };
+            changes.push((impact, format!("field `{field}` was added")));
+        }
+    }
+    if exhaustive && new.has_private_fields {
+        changes.push((
+            SemverImpact::Major,
+            "a private field was added to a struct with only public fields".to_string(),
+        ));
+    }
+
+    // Generic params and fields were compared above, the signature has the rest of the header
+    // like the where clause
+    if old.signature != new.signature {
+        changes.push((SemverImpact::Major, "definition changed".to_string()));
+    }
+    changes
+}
+
+fn generic_names(generics: &[GenericParamRepr]) -> Vec<String> {
+    generics.iter().map(|param| param.name.clone()).collect()
+}
+
+// Fields with the struct's generic params numbered, so renaming a param doesn't change them
+fn numbered_fields(struct_: &StructRepr) -> Vec<String> {
+    let names = generic_names(&struct_.generics);
+    struct_
+        .fields
+        .iter()
+        .map(|field| number_generics(field, &names))
+        .collect()
+}
+
+fn compare_traits(old: &TraitRepr, new: &TraitRepr) -> Vec<(SemverImpact, String)> {
+    let mut changes = compare_generics(&old.generics, &new.generics);
+
+    match (old.dyn_compatible, new.dyn_compatible) {
+        (true, false) => changes.push((
+            SemverImpact::Major,
+            "trait is no longer dyn compatible".to_string(),
+        )),
+        (false, true) => changes.push((
+            SemverImpact::Minor,
+            "trait became dyn compatible".to_string(),
+        )),
+        _ => {}
+    }
+
+    // Items and generic params are compared on their own, the signature has the rest of the header
+    // like supertraits and the where clause
+    if old.signature != new.signature {
+        changes.push((SemverImpact::Major, "trait definition changed".to_string()));
+    }
+    changes
+}
+
+fn compare_trait_items(old: &TraitItemRepr, new: &TraitItemRepr) -> Vec<(SemverImpact, String)> {
+    let mut changes = match (old.required, new.required) {
+        (false, true) => vec![(
+            SemverImpact::Major,
+            "default was removed, implementors now have to provide it".to_string(),
+        )],
+        (true, false) => vec![(SemverImpact::Minor, "default was added".to_string())],
+        _ => Vec::new(),
+    };
+    // Changing a default body or value isn't visible in the signature
+    if old.signature != new.signature {
+        changes.push((SemverImpact::Major, "signature changed".to_string()));
+    }
+    changes
+}
+
+// Params are matched by position, separately for lifetimes and other params since adding a
+// lifetime shifts the others. Renaming a param keeps its position, so it isn't a change.
+fn compare_generics(
+    old: &[GenericParamRepr],
+    new: &[GenericParamRepr],
+) -> Vec<(SemverImpact, String)> {
+    let is_lifetime = |param: &&GenericParamRepr| param.name.starts_with('\'');
+    let old_names = generic_names(old);
+    let new_names = generic_names(new);
+    let mut changes = Vec::new();
+    for lifetimes in [true, false] {
+        let old: Vec<_> = old.iter().filter(|p| is_lifetime(p) == lifetimes).collect();
+        let new: Vec<_> = new.iter().filter(|p| is_lifetime(p) == lifetimes).collect();
+        // Bounds and defaults, with the params numbered so a rename isn't a change
+        for (old_param, new_param) in old.iter().zip(&new) {
+            if number_generics(&old_param.repr, &old_names)
+                != number_generics(&new_param.repr, &new_names)
+            {
+                changes.push((
+                    SemverImpact::Major,
+                    format!("generic parameter `{}` changed", new_param.name),
+                ));
+            }
+        }
+        for param in old.iter().skip(new.len()) {
+            changes.push((
+                SemverImpact::Major,
+                format!("generic parameter `{}` was removed", param.name),
+            ));
+        }
+        for param in new.iter().skip(old.len()) {
+            if param.has_default {
+                changes.push((
+                    SemverImpact::Minor,
+                    format!("defaulted generic parameter `{}` was added", param.name),
+                ));
+            } else {
+                changes.push((
+                    SemverImpact::Major,
+                    format!(
+                        "generic parameter `{}` without a default was added",
+                        param.name
+                    ),
+                ));
+            }
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use rustdoc_types::Id;
+
+    use super::*;
+    use crate::{FnRepr, TraitItemKind};
+
+    fn module(
+        functions: Vec<FnRepr>,
+        structs: Vec<StructRepr>,
+        traits: Vec<TraitRepr>,
+    ) -> ModuleRepr {
+        ModuleRepr {
+            id: Id("0:0".to_string()),
+            name: "api".to_string(),
+            path: vec!["api".to_string()],
+            span: None,
+            functions,
+            structs,
+            traits,
+            modules: Vec::new(),
+        }
+    }
+
+    fn path(name: &str) -> Vec<String> {
+        name.split("::").map(str::to_string).collect()
+    }
+
+    fn function(name: &str, repr: &str, signature: &str) -> FnRepr {
+        FnRepr {
+            id: Id(name.to_string()),
+            name: name.to_string(),
+            path: path(&format!("api::{name}")),
+            span: None,
+            repr: repr.to_string(),
+            signature: signature.to_string(),
+            is_unsafe: false,
+            generics: Vec::new(),
+            input_types: Vec::new(),
+            output_types: Vec::new(),
+        }
+    }
+
+    fn struct_(fields: &[&str]) -> StructRepr {
+        StructRepr {
+            id: Id("S".to_string()),
+            name: "S".to_string(),
+            path: path("api::S"),
+            span: None,
+            repr: format!("pub struct S {{ {} }}", fields.join(", ")),
+            signature: "pub struct S {".to_string(),
+            generics: Vec::new(),
+            fields: fields.iter().map(|field| field.to_string()).collect(),
+            has_private_fields: false,
+            non_exhaustive: false,
+            auto_traits: vec!["Send".to_string(), "Sync".to_string()],
+            field_types: Vec::new(),
+        }
+    }
+
+    fn trait_(items: Vec<TraitItemRepr>) -> TraitRepr {
+        TraitRepr {
+            id: Id("T".to_string()),
+            name: "T".to_string(),
+            path: path("api::T"),
+            span: None,
+            repr: "pub trait T {}".to_string(),
+            signature: "pub trait T {".to_string(),
+            generics: Vec::new(),
+            items,
+            dyn_compatible: true,
+        }
+    }
+
+    fn method(name: &str, required: bool) -> TraitItemRepr {
+        let repr = format!("fn {name}(&self)");
+        TraitItemRepr {
+            id: Id(name.to_string()),
+            name: name.to_string(),
+            path: path(&format!("api::T::{name}")),
+            span: None,
+            signature: repr.clone(),
+            repr,
+            kind: TraitItemKind::Fn,
+            required,
+            input_types: Vec::new(),
+            output_types: Vec::new(),
+        }
+    }
+
+    fn param(name: &str, has_default: bool) -> GenericParamRepr {
+        GenericParamRepr {
+            name: name.to_string(),
+            repr: name.to_string(),
+            has_default,
+        }
+    }
+
+    fn trait_impacts(old: TraitRepr, new: TraitRepr) -> Vec<(String, SemverImpact)> {
+        impacts(&semver_check(
+            &module(Vec::new(), Vec::new(), vec![old]),
+            &module(Vec::new(), Vec::new(), vec![new]),
+        ))
+    }
+
+    fn impacts(report: &SemverReport) -> Vec<(String, SemverImpact)> {
+        report
+            .changes
+            .iter()
+            .map(|change| (change.path.join("::"), change.impact))
+            .collect()
+    }
+
+    fn struct_impacts(old: StructRepr, new: StructRepr) -> Vec<SemverImpact> {
+        let report = semver_check(
+            &module(Vec::new(), vec![old], Vec::new()),
+            &module(Vec::new(), vec![new], Vec::new()),
+        );
+        report.changes.iter().map(|change| change.impact).collect()
+    }
+
+    fn report_with_impact(impact: Option<SemverImpact>) -> SemverReport {
+        let changes = impact
+            .map(|impact| SemverChange {
+                path: path("api::f"),
+                kind: ItemKind::Fn,
+                impact,
+                reason: String::new(),
+            })
+            .into_iter()
+            .collect();
+        SemverReport { changes }
+    }
+
+    #[test]
+    fn removed_item_is_major() {
+        let old = module(
+            vec![function("f", "pub fn f()", "pub fn f()")],
+            Vec::new(),
+            Vec::new(),
+        );
+        let report = semver_check(&old, &module(Vec::new(), Vec::new(), Vec::new()));
+        assert_eq!(
+            impacts(&report),
+            [("api::f".to_string(), SemverImpact::Major)]
+        );
+    }
+
+    #[test]
+    fn added_item_is_minor() {
+        let new = module(
+            vec![function("f", "pub fn f()", "pub fn f()")],
+            Vec::new(),
+            Vec::new(),
+        );
+        let report = semver_check(&module(Vec::new(), Vec::new(), Vec::new()), &new);
+        assert_eq!(
+            impacts(&report),
+            [("api::f".to_string(), SemverImpact::Minor)]
+        );
+    }
+
+    #[test]
+    fn changed_signature_is_major() {
+        let old = module(
+            vec![function("f", "pub fn f(x: u8)", "pub fn f(_: u8)")],
+            Vec::new(),
+            Vec::new(),
+        );
+        let new = module(
+            vec![function("f", "pub fn f(x: u16)", "pub fn f(_: u16)")],
+            Vec::new(),
+            Vec::new(),
+        );
+        assert_eq!(semver_check(&old, &new).impact(), SemverImpact::Major);
+    }
+
+    #[test]
+    fn renamed_argument_is_not_a_change() {
+        let old = module(
+            vec![function("f", "pub fn f(x: u8)", "pub fn f(_: u8)")],
+            Vec::new(),
+            Vec::new(),
+        );
+        let new = module(
+            vec![function("f", "pub fn f(y: u8)", "pub fn f(_: u8)")],
+            Vec::new(),
+            Vec::new(),
+        );
+        assert!(semver_check(&old, &new).changes.is_empty());
+    }
+
+    #[test]
+    fn added_required_trait_method_is_major() {
+        let old = module(
+            Vec::new(),
+            Vec::new(),
+            vec![trait_(vec![method("a", true)])],
+        );
+        let new = module(
+            Vec::new(),
+            Vec::new(),
+            vec![trait_(vec![method("a", true), method("b", true)])],
+        );
+        assert_eq!(
+            impacts(&semver_check(&old, &new)),
+            [("api::T::b".to_string(), SemverImpact::Major)]
+        );
+    }
+
+    #[test]
+    fn added_provided_trait_method_is_minor() {
+        let old = module(
+            Vec::new(),
+            Vec::new(),
+            vec![trait_(vec![method("a", true)])],
+        );
+        let new = module(
+            Vec::new(),
+            Vec::new(),
+            vec![trait_(vec![method("a", true), method("b", false)])],
+        );
+        assert_eq!(
+            impacts(&semver_check(&old, &new)),
+            [("api::T::b".to_string(), SemverImpact::Minor)]
+        );
+    }
+
+    #[test]
+    fn required_method_of_new_trait_is_minor() {
+        let new = module(
+            Vec::new(),
+            Vec::new(),
+            vec![trait_(vec![method("a", true)])],
+        );
+        let report = semver_check(&module(Vec::new(), Vec::new(), Vec::new()), &new);
+        assert_eq!(report.impact(), SemverImpact::Minor);
+    }
+
+    #[test]
+    fn removed_default_is_major() {
+        let old = module(
+            Vec::new(),
+            Vec::new(),
+            vec![trait_(vec![method("a", false)])],
+        );
+        let new = module(
+            Vec::new(),
+            Vec::new(),
+            vec![trait_(vec![method("a", true)])],
+        );
+        assert_eq!(semver_check(&old, &new).impact(), SemverImpact::Major);
+    }
+
+    #[test]
+    fn added_default_with_changed_signature_is_major() {
+        let changed = TraitItemRepr {
+            signature: "fn a(&self, _: u8)".to_string(),
+            ..method("a", false)
+        };
+        assert_eq!(
+            trait_impacts(trait_(vec![method("a", true)]), trait_(vec![changed])),
+            [
+                ("api::T::a".to_string(), SemverImpact::Minor),
+                ("api::T::a".to_string(), SemverImpact::Major)
+            ]
+        );
+    }
+
+    #[test]
+    fn changed_trait_header_with_minor_change_is_major() {
+        let old = TraitRepr {
+            dyn_compatible: false,
+            ..trait_(Vec::new())
+        };
+        let new = TraitRepr {
+            signature: "pub trait T: Clone {".to_string(),
+            ..trait_(Vec::new())
+        };
+        assert_eq!(
+            trait_impacts(old, new),
+            [
+                ("api::T".to_string(), SemverImpact::Minor),
+                ("api::T".to_string(), SemverImpact::Major)
+            ]
+        );
+    }
+
+    #[test]
+    fn changed_struct_header_with_minor_change_is_major() {
+        let old = StructRepr {
+            auto_traits: vec!["Send".to_string()],
+            ..struct_(&[])
+        };
+        let new = StructRepr {
+            signature: "pub struct S\nwhere\n    u8: Copy,\n{".to_string(),
+            ..struct_(&[])
+        };
+        assert_eq!(
+            struct_impacts(old, new),
+            [SemverImpact::Minor, SemverImpact::Major]
+        );
+    }
+
+    #[test]
+    fn generic_params() {
+        let with_generics = |generics: Vec<GenericParamRepr>| StructRepr {
+            generics,
+            ..struct_(&[])
+        };
+        // Added without a default
+        assert_eq!(
+            struct_impacts(
+                with_generics(Vec::new()),
+                with_generics(vec![param("T", false)])
+            ),
+            [SemverImpact::Major]
+        );
+        // Added with a default
+        assert_eq!(
+            struct_impacts(
+                with_generics(Vec::new()),
+                with_generics(vec![param("T", true)])
+            ),
+            [SemverImpact::Minor]
+        );
+        // Removed
+        assert_eq!(
+            struct_impacts(
+                with_generics(vec![param("T", false)]),
+                with_generics(Vec::new())
+            ),
+            [SemverImpact::Major]
+        );
+        // Renamed
+        assert_eq!(
+            struct_impacts(
+                with_generics(vec![param("'a", false), param("T", false)]),
+                with_generics(vec![param("'b", false), param("U", false)])
+            ),
+            []
+        );
+        // Bound changed, next to a defaulted param that was added
+        let bounded = |repr: &str| GenericParamRepr {
+            repr: repr.to_string(),
+            ..param("T", false)
+        };
+        assert_eq!(
+            struct_impacts(
+                with_generics(vec![bounded("T: Clone")]),
+                with_generics(vec![bounded("T: Clone + Send"), param("U", true)])
+            ),
+            [SemverImpact::Major, SemverImpact::Minor]
+        );
+        // Bound kept while renamed
+        let renamed = GenericParamRepr {
+            name: "U".to_string(),
+            repr: "U: Clone".to_string(),
+            has_default: false,
+        };
+        assert_eq!(
+            struct_impacts(
+                with_generics(vec![bounded("T: Clone")]),
+                with_generics(vec![renamed])
+            ),
+            []
+        );
+    }
+
+    #[test]
+    fn renamed_generic_in_fields_is_not_a_change() {
+        let old = StructRepr {
+            generics: vec![param("T", false)],
+            ..struct_(&["pub value: T"])
+        };
+        let new = StructRepr {
+            generics: vec![param("U", false)],
+            ..struct_(&["pub value: U"])
+        };
+        assert_eq!(struct_impacts(old, new), []);
+    }
+
+    #[test]
+    fn non_exhaustive() {
+        let non_exhaustive = StructRepr {
+            non_exhaustive: true,
+            ..struct_(&[])
+        };
+        assert_eq!(
+            struct_impacts(struct_(&[]), non_exhaustive),
+            [SemverImpact::Major]
+        );
+        let non_exhaustive = StructRepr {
+            non_exhaustive: true,
+            ..struct_(&[])
+        };
+        assert_eq!(
+            struct_impacts(non_exhaustive, struct_(&[])),
+            [SemverImpact::Minor]
+        );
+    }
+
+    #[test]
+    fn auto_traits() {
+        let not_sync = StructRepr {
+            auto_traits: vec!["Send".to_string()],
+            ..struct_(&[])
+        };
+        assert_eq!(
+            struct_impacts(struct_(&[]), not_sync),
+            [SemverImpact::Major]
+        );
+        let not_sync = StructRepr {
+            auto_traits: vec!["Send".to_string()],
+            ..struct_(&[])
+        };
+        assert_eq!(
+            struct_impacts(not_sync, struct_(&[])),
+            [SemverImpact::Minor]
+        );
+    }
+
+    #[test]
+    fn added_fields() {
+        // Breaks struct literals and patterns
+        assert_eq!(
+            struct_impacts(
+                struct_(&["pub a: u8"]),
+                struct_(&["pub a: u8", "pub b: u8"])
+            ),
+            [SemverImpact::Major]
+        );
+        // Can't be constructed outside the crate anyway
+        let private = |fields: &[&str]| StructRepr {
+            has_private_fields: true,
+            ..struct_(fields)
+        };
+        assert_eq!(
+            struct_impacts(
+                private(&["pub a: u8"]),
+                private(&["pub a: u8", "pub b: u8"])
+            ),
+            [SemverImpact::Minor]
+        );
+        assert_eq!(
+            struct_impacts(struct_(&["pub a: u8"]), struct_(&[])),
+            [SemverImpact::Major]
+        );
+    }
+
+    #[test]
+    fn next_version() {
+        let cases = [
+            ("1.2.3", None, "1.2.4"),
+            ("1.2.3", Some(SemverImpact::Patch), "1.2.4"),
+            ("1.2.3", Some(SemverImpact::Minor), "1.3.0"),
+            ("1.2.3", Some(SemverImpact::Major), "2.0.0"),
+            // The minor version is the major one before 1.0
+            ("0.2.3", Some(SemverImpact::Patch), "0.2.4"),
+            ("0.2.3", Some(SemverImpact::Minor), "0.2.4"),
+            ("0.2.3", Some(SemverImpact::Major), "0.3.0"),
+            // Every release of a 0.0 version is breaking
+            ("0.0.3", Some(SemverImpact::Patch), "0.0.4"),
+            ("0.0.3", Some(SemverImpact::Minor), "0.0.4"),
+            ("0.0.3", Some(SemverImpact::Major), "0.0.4"),
+            ("1.0.0-beta.1+build", Some(SemverImpact::Minor), "1.1.0"),
+        ];
+        for (version, impact, expected) in cases {
+            assert_eq!(
+                report_with_impact(impact).next_version(version).as_deref(),
+                Some(expected),
+                "{version} {impact:?}"
+            );
+        }
+        assert_eq!(report_with_impact(None).next_version("1.2"), None);
+        assert_eq!(report_with_impact(None).next_version("v1.2.3"), None);
+    }
+}
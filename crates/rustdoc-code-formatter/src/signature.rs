@@ -0,0 +1,243 @@
+/// The parts of an item header that can be broken across lines
+pub(crate) struct Signature {
+    /// Everything up to the generic params, e.g. `pub fn name`
+    pub(crate) head: String,
+    pub(crate) generics: Vec<String>,
+    /// Function inputs or tuple struct fields, `None` if the item has no parenthesized list
+    pub(crate) args: Option<Vec<String>>,
+    /// Everything between the args and the where clause, e.g. ` -> Output` or `: Bound`
+    pub(crate) tail: String,
+    pub(crate) where_predicates: Vec<String>,
+    pub(crate) end: SignatureEnd,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SignatureEnd {
+    None,
+    Semicolon,
+    /// Opens a block, the caller is responsible for closing it
+    Brace,
+}
+
+impl Signature {
+    /// Formats the signature on one line if it fits in `max_width`, otherwise breaks generic
+    /// params and args onto their own lines like rustfmt does. Where clauses always get lines of
+    /// their own, as rustfmt never keeps them on the same line.
+    pub(crate) fn format(&self, max_width: usize) -> String {
+        let generics = self.generics_inline();
+        if self.where_predicates.is_empty() {
+            let args = self.args_inline();
+            let mut one_line = format!("{}{generics}{args}{}", self.head, self.tail);
+            one_line += match self.end {
+                SignatureEnd::None => "",
+                SignatureEnd::Semicolon => ";",
+                SignatureEnd::Brace => " {",
+            };
+            if width(&one_line) <= max_width {
+                return one_line;
+            }
+        }
+
+        let mut s = self.head.clone();
+        if width(&format!("{s}{generics}(")) <= max_width {
+            s += &generics;
+        } else {
+            s += &format!("<{}\n>", block_items(&self.generics));
+        }
+        if let Some(args) = &self.args {
+            let inline = format!("{args}{}", self.tail, args = self.args_inline());
+            if args.is_empty() || width(last_line(&s)) + width(&inline) <= max_width {
+                s += &inline;
+            } else {
+                s += &format!("({}\n){}", block_items(args), self.tail);
+            }
+        } else {
+            s += &self.tail;
+        }
+
+        if self.where_predicates.is_empty() {
+            s += match self.end {
+                SignatureEnd::None => "",
+                SignatureEnd::Semicolon => ";",
+                SignatureEnd::Brace => " {",
+            };
+        } else {
+            s += "\nwhere";
+            s += &block_items(&self.where_predicates);
+            match self.end {
+                SignatureEnd::None => {}
+                // rustfmt drops the trailing comma before the semicolon
+                SignatureEnd::Semicolon => {
+                    s.pop();
+                    s += ";";
+                }
+                SignatureEnd::Brace => s += "\n{",
+            }
+        }
+        s
+    }
+
+    fn generics_inline(&self) -> String {
+        if self.generics.is_empty() {
+            "".to_string()
+        } else {
+            format!("<{}>", self.generics.join(", "))
+        }
+    }
+
+    fn args_inline(&self) -> String {
+        match &self.args {
+            Some(args) => format!("({})", args.join(", ")),
+            None => "".to_string(),
+        }
+    }
+}
+
+// One item per line with a trailing comma
+fn block_items(items: &[String]) -> String {
+    let mut s = String::new();
+    for item in items {
+        s += &format!("\n    {item},");
+    }
+    s
+}
+
+fn last_line(s: &str) -> &str {
+    s.rsplit('\n').next().unwrap_or_default()
+}
+
+fn width(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// Indents every line after the first, for nesting a formatted signature inside a block
+pub(crate) fn indent_continuation(s: &str, indent: &str) -> String {
+    s.replace('\n', &format!("\n{indent}"))
+}
+
+/// Replaces the generic params named in `names` with their position, `T` becomes `_0` and `'a`
+/// becomes `'_0`, so renaming a param doesn't change the text. Paths like `other::T` are kept.
+pub(crate) fn number_generics(text: &str, names: &[String]) -> String {
+    let mut numbered = String::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c != '\'' && !c.is_alphabetic() && c != '_' {
+            numbered.push(c);
+            continue;
+        }
+        let mut end = start + c.len_utf8();
+        while let Some(&(i, next)) = chars.peek() {
+            if !next.is_alphanumeric() && next != '_' {
+                break;
+            }
+            end = i + next.len_utf8();
+            chars.next();
+        }
+        let token = &text[start..end];
+        match names.iter().position(|name| name == token) {
+            Some(i) if !numbered.ends_with("::") => {
+                let lifetime = if c == '\'' { "'" } else { "" };
+                numbered += &format!("{lifetime}_{i}");
+            }
+            _ => numbered += token,
+        }
+    }
+    numbered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Signature, SignatureEnd, number_generics};
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    fn function(generics: &[&str], args: &[&str], where_predicates: &[&str]) -> Signature {
+        Signature {
+            head: "pub fn f".to_string(),
+            generics: strings(generics),
+            args: Some(strings(args)),
+            tail: " -> u8".to_string(),
+            where_predicates: strings(where_predicates),
+            end: SignatureEnd::None,
+        }
+    }
+
+    #[test]
+    fn keeps_short_signatures_on_one_line() {
+        let signature = function(&["T: Clone"], &["a: T", "b: u8"], &[]);
+        assert_eq!(
+            signature.format(100),
+            "pub fn f<T: Clone>(a: T, b: u8) -> u8"
+        );
+        // Exactly at the limit
+        assert_eq!(
+            signature.format(37),
+            "pub fn f<T: Clone>(a: T, b: u8) -> u8"
+        );
+    }
+
+    #[test]
+    fn breaks_long_args() {
+        let long = "a".repeat(40);
+        let args = [format!("{long}: u8"), format!("b{long}: u8")];
+        let signature = function(&[], &[&args[0], &args[1]], &[]);
+        assert_eq!(
+            signature.format(100),
+            format!("pub fn f(\n    {long}: u8,\n    b{long}: u8,\n) -> u8")
+        );
+    }
+
+    #[test]
+    fn breaks_long_generics() {
+        let bound = format!("T: {}", "Tr + ".repeat(20) + "Tr");
+        let signature = function(&[&bound], &["t: T"], &[]);
+        assert_eq!(
+            signature.format(100),
+            format!("pub fn f<\n    {bound},\n>(t: T) -> u8")
+        );
+    }
+
+    #[test]
+    fn always_breaks_where_clauses() {
+        let signature = function(&["T"], &["t: T"], &["T: Clone"]);
+        assert_eq!(
+            signature.format(100),
+            "pub fn f<T>(t: T) -> u8\nwhere\n    T: Clone,"
+        );
+        let long = format!("T: {}", "Tr + ".repeat(30) + "Tr");
+        let signature = Signature {
+            end: SignatureEnd::Brace,
+            ..function(&["T"], &["t: T"], &[&long, "u8: Copy"])
+        };
+        assert_eq!(
+            signature.format(100),
+            format!("pub fn f<T>(t: T) -> u8\nwhere\n    {long},\n    u8: Copy,\n{{")
+        );
+    }
+
+    #[test]
+    fn ends_where_clauses_with_semicolon() {
+        let signature = Signature {
+            head: "type A".to_string(),
+            generics: strings(&["T"]),
+            args: None,
+            tail: String::new(),
+            where_predicates: strings(&["T: Clone"]),
+            end: SignatureEnd::Semicolon,
+        };
+        assert_eq!(signature.format(100), "type A<T>\nwhere\n    T: Clone;");
+    }
+
+    #[test]
+    fn numbers_generics_by_position() {
+        let names = ["'a".to_string(), "T".to_string()];
+        assert_eq!(
+            number_generics("fn f<'a, T: Tr>(x: &'a T) -> other::T where T: 'a", &names),
+            "fn f<'_0, _1: Tr>(x: &'_0 _1) -> other::T where _1: '_0"
+        );
+        // Only whole identifiers
+        assert_eq!(number_generics("Tx<T>", &names), "Tx<_1>");
+    }
+}
@@ -0,0 +1,355 @@
+//! Traversal of the documentation model.
+//!
+//! Each method of [`Visitor`] and [`VisitorMut`] defaults to calling the matching `walk_*`
+//! function, which visits the children of the node. Override a method to handle a node and call
+//! the walk function from it to keep descending, or leave it out to skip the children.
+
+use crate::{FnRepr, GenericParamRepr, ModuleRepr, StructRepr, TraitItemRepr, TraitRepr};
+
+pub trait Visitor<'a> {
+    fn visit_module(&mut self, module: &'a ModuleRepr) {
+        walk_module(self, module);
+    }
+
+    fn visit_fn(&mut self, func: &'a FnRepr) {
+        walk_fn(self, func);
+    }
+
+    fn visit_struct(&mut self, struct_: &'a StructRepr) {
+        walk_struct(self, struct_);
+    }
+
+    fn visit_field(&mut self, _field: &'a str) {}
+
+    fn visit_trait(&mut self, trait_: &'a TraitRepr) {
+        walk_trait(self, trait_);
+    }
+
+    fn visit_trait_item(&mut self, _item: &'a TraitItemRepr) {}
+
+    fn visit_generic_param(&mut self, _param: &'a GenericParamRepr) {}
+}
+
+/// Visits functions, structs, traits and then submodules, each in the order they're stored
+pub fn walk_module<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, module: &'a ModuleRepr) {
+    for func in &module.functions {
+        visitor.visit_fn(func);
+    }
+    for struct_ in &module.structs {
+        visitor.visit_struct(struct_);
+    }
+    for trait_ in &module.traits {
+        visitor.visit_trait(trait_);
+    }
+    for module in &module.modules {
+        visitor.visit_module(module);
+    }
+}
+
+pub fn walk_fn<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, func: &'a FnRepr) {
+    for param in &func.generics {
+        visitor.visit_generic_param(param);
+    }
+}
+
+pub fn walk_struct<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, struct_: &'a StructRepr) {
+    for param in &struct_.generics {
+        visitor.visit_generic_param(param);
+    }
+    for field in &struct_.fields {
+        visitor.visit_field(field);
+    }
+}
+
+pub fn walk_trait<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, trait_: &'a TraitRepr) {
+    for param in &trait_.generics {
+        visitor.visit_generic_param(param);
+    }
+    for item in &trait_.items {
+        visitor.visit_trait_item(item);
+    }
+}
+
+/// Like [`Visitor`], but with mutable access to allow rewriting the model in place
+pub trait VisitorMut {
+    fn visit_module_mut(&mut self, module: &mut ModuleRepr) {
+        walk_module_mut(self, module);
+    }
+
+    fn visit_fn_mut(&mut self, func: &mut FnRepr) {
+        walk_fn_mut(self, func);
+    }
+
+    fn visit_struct_mut(&mut self, struct_: &mut StructRepr) {
+        walk_struct_mut(self, struct_);
+    }
+
+    fn visit_field_mut(&mut self, _field: &mut String) {}
+
+    fn visit_trait_mut(&mut self, trait_: &mut TraitRepr) {
+        walk_trait_mut(self, trait_);
+    }
+
+    fn visit_trait_item_mut(&mut self, _item: &mut TraitItemRepr) {}
+
+    fn visit_generic_param_mut(&mut self, _param: &mut GenericParamRepr) {}
+}
+
+pub fn walk_module_mut<V: VisitorMut + ?Sized>(visitor: &mut V, module: &mut ModuleRepr) {
+    for func in &mut module.functions {
+        visitor.visit_fn_mut(func);
+    }
+    for struct_ in &mut module.structs {
+        visitor.visit_struct_mut(struct_);
+    }
+    for trait_ in &mut module.traits {
+        visitor.visit_trait_mut(trait_);
+    }
+    for module in &mut module.modules {
+        visitor.visit_module_mut(module);
+    }
+}
+
+pub fn walk_fn_mut<V: VisitorMut + ?Sized>(visitor: &mut V, func: &mut FnRepr) {
+    for param in &mut func.generics {
+        visitor.visit_generic_param_mut(param);
+    }
+}
+
+pub fn walk_struct_mut<V: VisitorMut + ?Sized>(visitor: &mut V, struct_: &mut StructRepr) {
+    for param in &mut struct_.generics {
+        visitor.visit_generic_param_mut(param);
+    }
+    for field in &mut struct_.fields {
+        visitor.visit_field_mut(field);
+    }
+}
+
+pub fn walk_trait_mut<V: VisitorMut + ?Sized>(visitor: &mut V, trait_: &mut TraitRepr) {
+    for param in &mut trait_.generics {
+        visitor.visit_generic_param_mut(param);
+    }
+    for item in &mut trait_.items {
+        visitor.visit_trait_item_mut(item);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Visitor, VisitorMut, walk_fn, walk_fn_mut, walk_module, walk_struct, walk_struct_mut,
+        walk_trait,
+    };
+    use crate::{
+        FnRepr, GenericParamRepr, Id, ModuleRepr, StructRepr, TraitItemKind, TraitItemRepr,
+        TraitRepr,
+    };
+
+    fn param(name: &str) -> GenericParamRepr {
+        GenericParamRepr {
+            name: name.to_string(),
+            repr: name.to_string(),
+            has_default: false,
+        }
+    }
+
+    fn function(name: &str, generics: Vec<GenericParamRepr>) -> FnRepr {
+        FnRepr {
+            id: Id(name.to_string()),
+            name: name.to_string(),
+            path: vec![name.to_string()],
+            span: None,
+            repr: String::new(),
+            signature: String::new(),
+            is_unsafe: false,
+            generics,
+            input_types: Vec::new(),
+            output_types: Vec::new(),
+        }
+    }
+
+    fn module(name: &str, functions: Vec<FnRepr>, modules: Vec<ModuleRepr>) -> ModuleRepr {
+        ModuleRepr {
+            id: Id(name.to_string()),
+            name: name.to_string(),
+            path: vec![name.to_string()],
+            span: None,
+            functions,
+            structs: Vec::new(),
+            traits: Vec::new(),
+            modules,
+        }
+    }
+
+    fn model() -> ModuleRepr {
+        let mut root = module(
+            "root",
+            vec![function("f", vec![param("T")])],
+            vec![module("sub", vec![function("g", Vec::new())], Vec::new())],
+        );
+        root.structs.push(StructRepr {
+            id: Id("S".to_string()),
+            name: "S".to_string(),
+            path: vec!["S".to_string()],
+            span: None,
+            repr: String::new(),
+            signature: String::new(),
+            generics: vec![param("U")],
+            fields: vec!["pub a: u8".to_string(), "pub b: u8".to_string()],
+            has_private_fields: false,
+            non_exhaustive: false,
+            auto_traits: Vec::new(),
+            field_types: Vec::new(),
+        });
+        root.traits.push(TraitRepr {
+            id: Id("Tr".to_string()),
+            name: "Tr".to_string(),
+            path: vec!["Tr".to_string()],
+            span: None,
+            repr: String::new(),
+            signature: String::new(),
+            generics: vec![param("V")],
+            items: vec![TraitItemRepr {
+                id: Id("m".to_string()),
+                name: "m".to_string(),
+                path: vec!["Tr".to_string(), "m".to_string()],
+                span: None,
+                repr: String::new(),
+                signature: String::new(),
+                kind: TraitItemKind::Fn,
+                required: true,
+                input_types: Vec::new(),
+                output_types: Vec::new(),
+            }],
+            dyn_compatible: true,
+        });
+        root
+    }
+
+    #[derive(Default)]
+    struct Log(Vec<String>);
+
+    impl<'a> Visitor<'a> for Log {
+        fn visit_module(&mut self, module: &'a ModuleRepr) {
+            self.0.push(format!("module {}", module.name));
+            walk_module(self, module);
+        }
+
+        fn visit_fn(&mut self, func: &'a FnRepr) {
+            self.0.push(format!("fn {}", func.name));
+            walk_fn(self, func);
+        }
+
+        fn visit_struct(&mut self, struct_: &'a StructRepr) {
+            self.0.push(format!("struct {}", struct_.name));
+            walk_struct(self, struct_);
+        }
+
+        fn visit_field(&mut self, field: &'a str) {
+            self.0.push(format!("field {field}"));
+        }
+
+        fn visit_trait(&mut self, trait_: &'a TraitRepr) {
+            self.0.push(format!("trait {}", trait_.name));
+            walk_trait(self, trait_);
+        }
+
+        fn visit_trait_item(&mut self, item: &'a TraitItemRepr) {
+            self.0.push(format!("item {}", item.name));
+        }
+
+        fn visit_generic_param(&mut self, param: &'a GenericParamRepr) {
+            self.0.push(format!("param {}", param.name));
+        }
+    }
+
+    #[test]
+    fn visit_order() {
+        let model = model();
+        let mut log = Log::default();
+        log.visit_module(&model);
+        assert_eq!(
+            log.0,
+            [
+                "module root",
+                "fn f",
+                "param T",
+                "struct S",
+                "param U",
+                "field pub a: u8",
+                "field pub b: u8",
+                "trait Tr",
+                "param V",
+                "item m",
+                "module sub",
+                "fn g",
+            ]
+        );
+    }
+
+    #[test]
+    fn skipping_children() {
+        // Collects function names, the default `visit_module` still descends into submodules
+        #[derive(Default)]
+        struct Functions(Vec<String>);
+
+        impl<'a> Visitor<'a> for Functions {
+            fn visit_fn(&mut self, func: &'a FnRepr) {
+                self.0.push(func.name.clone());
+            }
+
+            fn visit_struct(&mut self, _struct: &'a StructRepr) {}
+
+            fn visit_trait(&mut self, _trait: &'a TraitRepr) {}
+
+            fn visit_generic_param(&mut self, param: &'a GenericParamRepr) {
+                panic!("visited {}", param.name);
+            }
+        }
+
+        let model = model();
+        let mut functions = Functions::default();
+        functions.visit_module(&model);
+        assert_eq!(functions.0, ["f", "g"]);
+    }
+
+    #[test]
+    fn edits_show_up_in_the_model() {
+        struct Rename;
+
+        impl VisitorMut for Rename {
+            fn visit_fn_mut(&mut self, func: &mut FnRepr) {
+                func.name = func.name.to_uppercase();
+                walk_fn_mut(self, func);
+            }
+
+            fn visit_struct_mut(&mut self, struct_: &mut StructRepr) {
+                struct_.has_private_fields = true;
+                walk_struct_mut(self, struct_);
+            }
+
+            fn visit_field_mut(&mut self, field: &mut String) {
+                *field = field.trim_start_matches("pub ").to_string();
+            }
+
+            fn visit_trait_item_mut(&mut self, item: &mut TraitItemRepr) {
+                item.required = false;
+            }
+
+            fn visit_generic_param_mut(&mut self, param: &mut GenericParamRepr) {
+                param.repr += ": Clone";
+            }
+        }
+
+        let mut model = model();
+        Rename.visit_module_mut(&mut model);
+        assert_eq!(model.functions[0].name, "F");
+        assert_eq!(model.modules[0].functions[0].name, "G");
+        assert_eq!(model.functions[0].generics[0].repr, "T: Clone");
+        assert!(model.structs[0].has_private_fields);
+        assert_eq!(model.structs[0].fields, ["a: u8", "b: u8"]);
+        assert_eq!(model.structs[0].generics[0].repr, "U: Clone");
+        assert!(!model.traits[0].items[0].required);
+        assert_eq!(model.traits[0].generics[0].repr, "V: Clone");
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json::Value;
+
+pub(crate) struct Member {
+    pub(crate) manifest_path: PathBuf,
+    /// Name of the library target, which is how other crates refer to it
+    pub(crate) crate_name: String,
+    /// Directories of every crate this one depends on through a `path` dependency, directly or
+    /// not. Their items can show up in the docs through re-exports and inlining.
+    pub(crate) path_dependencies: Vec<PathBuf>,
+}
+
+/// Every library in the workspace if `manifest_path` is the workspace root, otherwise only the
+/// package it points to
+pub(crate) fn members(manifest_path: &Path) -> Vec<Member> {
+    let metadata = metadata(manifest_path);
+    let mut path_dependencies = PathDependencies::default();
+    path_dependencies.add_packages(&metadata);
+
+    let manifest_path = manifest_path.canonicalize().unwrap();
+    let workspace_root = PathBuf::from(metadata["workspace_root"].as_str().unwrap());
+    let is_workspace_root = workspace_root.join("Cargo.toml") == manifest_path;
+
+    let mut members: Vec<_> = metadata["packages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|package| {
+            let package_manifest = PathBuf::from(package["manifest_path"].as_str()?);
+            if !is_workspace_root && package_manifest != manifest_path {
+                return None;
+            }
+            let lib = package["targets"].as_array()?.iter().find(|target| {
+                target["kind"].as_array().is_some_and(|kinds| {
+                    kinds
+                        .iter()
+                        .any(|kind| kind.as_str().is_some_and(is_library_kind))
+                })
+            })?;
+            Some(Member {
+                crate_name: lib["name"].as_str()?.replace('-', "_"),
+                path_dependencies: path_dependencies.transitive(package_manifest.parent()?),
+                manifest_path: package_manifest,
+            })
+        })
+        .collect();
+    members.sort_by(|a, b| a.crate_name.cmp(&b.crate_name));
+    members
+}
+
+fn is_library_kind(kind: &str) -> bool {
+    kind.ends_with("lib") || kind == "proc-macro"
+}
+
+fn metadata(manifest_path: &Path) -> Value {
+    let output = Command::new("cargo")
+        .args([
+            "metadata",
+            "--no-deps",
+            "--format-version",
+            "1",
+            "--manifest-path",
+        ])
+        .arg(manifest_path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+/// The direct path dependencies of each package directory. Packages outside the workspace aren't
+/// in its metadata, so they're looked up when they're first reached.
+#[derive(Default)]
+struct PathDependencies {
+    direct: BTreeMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl PathDependencies {
+    fn add_packages(&mut self, metadata: &Value) {
+        for package in metadata["packages"].as_array().unwrap() {
+            let manifest_path = Path::new(package["manifest_path"].as_str().unwrap());
+            // Dev dependencies aren't used when documenting the library
+            let dependencies = package["dependencies"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|dependency| dependency["kind"].as_str() != Some("dev"))
+                .filter_map(|dependency| dependency["path"].as_str().map(PathBuf::from))
+                .collect();
+            self.direct
+                .insert(manifest_path.parent().unwrap().to_owned(), dependencies);
+        }
+    }
+
+    fn transitive(&mut self, package_dir: &Path) -> Vec<PathBuf> {
+        let mut found = Vec::new();
+        let mut stack = vec![package_dir.to_owned()];
+        while let Some(dir) = stack.pop() {
+            if !self.direct.contains_key(&dir) {
+                let metadata = metadata(&dir.join("Cargo.toml"));
+                self.add_packages(&metadata);
+            }
+            for dependency in self.direct.get(&dir).into_iter().flatten() {
+                if dependency != package_dir && !found.contains(dependency) {
+                    found.push(dependency.clone());
+                    stack.push(dependency.clone());
+                }
+            }
+        }
+        found.sort();
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::members;
+
+    #[test]
+    fn finds_path_dependencies_of_members() {
+        let workspace = Path::new("examples/test-apis/workspace_api")
+            .canonicalize()
+            .unwrap();
+        let members = members(&workspace.join("Cargo.toml"));
+        let names: Vec<_> = members.iter().map(|m| m.crate_name.as_str()).collect();
+        assert_eq!(names, ["app_api", "core_api"]);
+        assert_eq!(members[0].path_dependencies, [workspace.join("core_api")]);
+        assert!(members[1].path_dependencies.is_empty());
+    }
+}
@@ -0,0 +1,93 @@
+use rustdoc_code_formatter::{Builder, ItemOrder, ModuleRepr};
+
+const TEST_API: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/examples/test-apis/test_api/Cargo.toml"
+);
+
+fn build(order: ItemOrder) -> Vec<ModuleRepr> {
+    Builder::default()
+        .manifest_path(TEST_API)
+        .order(order)
+        .build()
+}
+
+fn module<'a>(crate_: &'a ModuleRepr, name: &str) -> &'a ModuleRepr {
+    crate_.modules.iter().find(|m| m.name == name).unwrap()
+}
+
+// The names of the submodules, the first structs and the items of `MixedItems`
+fn names(crates: &[ModuleRepr]) -> (Vec<&str>, Vec<&str>, Vec<&str>) {
+    let crate_ = &crates[0];
+    let modules = crate_.modules.iter().map(|m| m.name.as_str()).collect();
+    let structs = module(crate_, "structs")
+        .structs
+        .iter()
+        .take(4)
+        .map(|s| s.name.as_str())
+        .collect();
+    let mixed = module(crate_, "traits")
+        .traits
+        .iter()
+        .find(|t| t.name == "MixedItems")
+        .unwrap();
+    let items = mixed.items.iter().map(|i| i.name.as_str()).collect();
+    (modules, structs, items)
+}
+
+#[test]
+fn source_order() {
+    let crates = build(ItemOrder::Source);
+    let (modules, structs, items) = names(&crates);
+    assert_eq!(modules, ["functions", "structs", "traits"]);
+    assert_eq!(
+        structs,
+        ["Unit", "Plain", "PrivateField", "TupleStructSingle"]
+    );
+    assert_eq!(
+        items,
+        ["provided", "DEFAULT", "required", "Type", "REQUIRED"]
+    );
+}
+
+#[test]
+fn alphabetical_order() {
+    let crates = build(ItemOrder::Alphabetical);
+    let (modules, structs, items) = names(&crates);
+    assert_eq!(modules, ["functions", "structs", "traits"]);
+    assert_eq!(
+        structs,
+        ["ConstArg", "OnlyConstArg", "Plain", "PrivateField"]
+    );
+    assert_eq!(
+        items,
+        ["DEFAULT", "REQUIRED", "Type", "provided", "required"]
+    );
+}
+
+#[test]
+fn kind_order() {
+    let crates = build(ItemOrder::Kind);
+    let (modules, structs, items) = names(&crates);
+    assert_eq!(modules, ["functions", "structs", "traits"]);
+    // Within a kind, items stay in source order
+    assert_eq!(
+        structs,
+        ["Unit", "Plain", "PrivateField", "TupleStructSingle"]
+    );
+    // Associated types, then constants and methods, required ones before provided ones
+    assert_eq!(
+        items,
+        ["Type", "REQUIRED", "DEFAULT", "required", "provided"]
+    );
+}
+
+// rustdoc's index is a HashMap, so every build iterates it in a different order
+#[test]
+fn output_is_deterministic() {
+    for order in [ItemOrder::Source, ItemOrder::Alphabetical, ItemOrder::Kind] {
+        let first = format!("{:#?}", build(order));
+        let second = format!("{:#?}", build(order));
+        assert_eq!(first, second, "{order:?}");
+    }
+}
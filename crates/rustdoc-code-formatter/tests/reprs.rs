@@ -0,0 +1,106 @@
+use rustdoc_code_formatter::{Builder, ModuleRepr};
+
+const TEST_API: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/examples/test-apis/test_api/Cargo.toml"
+);
+
+fn functions() -> ModuleRepr {
+    let mut crates = Builder::default().manifest_path(TEST_API).build();
+    crates
+        .remove(0)
+        .modules
+        .into_iter()
+        .find(|module| module.name == "functions")
+        .unwrap()
+}
+
+fn assert_reprs(module: &ModuleRepr, expected: &[(&str, &[&str])]) {
+    for (name, lines) in expected {
+        let function = module
+            .functions
+            .iter()
+            .find(|function| function.name == *name)
+            .unwrap_or_else(|| panic!("no function `{name}`"));
+        assert_eq!(function.repr, lines.join("\n"), "{name}");
+    }
+}
+
+#[test]
+fn argument_position_impl_trait() {
+    // Synthetic params leave no `<>`, stray commas or where predicates behind
+    assert_reprs(
+        &functions(),
+        &[
+            (
+                "synthetic_arg",
+                &["pub fn synthetic_arg(t: impl Simple) -> impl Simple"],
+            ),
+            (
+                "impl_into",
+                &["pub fn impl_into(s: impl Into<String>) -> String"],
+            ),
+            (
+                "impl_multiple",
+                &["pub fn impl_multiple<T>(t: impl Simple + AsRef<T>) -> impl Simple"],
+            ),
+            (
+                "impl_ref_multiple",
+                &["pub fn impl_ref_multiple(d: &(impl Debug + Send))"],
+            ),
+            (
+                "impl_generic_arg",
+                &["pub fn impl_generic_arg(v: Vec<impl Display>)"],
+            ),
+            (
+                "impl_with_where",
+                &[
+                    "pub fn impl_with_where<T>(i: impl Iterator<Item = T>)",
+                    "where",
+                    "    T: Debug,",
+                ],
+            ),
+        ],
+    );
+}
+
+#[test]
+fn higher_ranked_bounds() {
+    assert_reprs(
+        &functions(),
+        &[(
+            "impl_higher_ranked",
+            &["pub fn impl_higher_ranked(f: impl for<'a> Fn(&'a str) -> &'a str)"],
+        )],
+    );
+}
+
+#[test]
+fn precise_capturing() {
+    assert_reprs(
+        &functions(),
+        &[(
+            "impl_precise_capturing",
+            &["pub fn impl_precise_capturing<'a, T>(t: &'a T) -> impl Sized + use<'a, T>"],
+        )],
+    );
+}
+
+#[test]
+fn maybe_sized() {
+    assert_reprs(
+        &functions(),
+        &[("maybe_sized", &["pub fn maybe_sized<T: ?Sized>(t: &T)"])],
+    );
+}
+
+#[test]
+fn associated_type_bounds() {
+    assert_reprs(
+        &functions(),
+        &[(
+            "impl_assoc_bound",
+            &["pub fn impl_assoc_bound(i: impl Iterator<Item: Debug>)"],
+        )],
+    );
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "serde")]
+
+use rustdoc_code_formatter::{Builder, Document, SCHEMA_VERSION};
+use serde_json::Value;
+
+const TEST_API: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/examples/test-apis/test_api/Cargo.toml"
+);
+
+fn document() -> Document {
+    Document::new(Builder::default().manifest_path(TEST_API).build())
+}
+
+#[test]
+fn round_trips() {
+    let document = document();
+    let json = serde_json::to_string(&document).unwrap();
+    let parsed: Document = serde_json::from_str(&json).unwrap();
+    // The model doesn't implement `PartialEq`, `Debug` shows every field
+    assert_eq!(format!("{parsed:#?}"), format!("{document:#?}"));
+    assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+}
+
+#[test]
+fn schema_version() {
+    let document = document();
+    assert_eq!(document.schema_version, SCHEMA_VERSION);
+    assert!(document.is_supported());
+
+    let mut json = serde_json::to_value(&document).unwrap();
+    assert_eq!(json["schema_version"], SCHEMA_VERSION);
+    json["schema_version"] = Value::from(SCHEMA_VERSION + 1);
+    let newer: Document = serde_json::from_value(json).unwrap();
+    assert!(!newer.is_supported());
+}
+
+#[test]
+fn ignores_unknown_fields() {
+    let mut json = serde_json::to_value(document()).unwrap();
+    json["added_later"] = Value::from(true);
+    json["crates"][0]["modules"][0]["functions"][0]["added_later"] = Value::from(true);
+    let document: Document = serde_json::from_value(json).unwrap();
+    assert!(document.is_supported());
+}
@@ -0,0 +1,34 @@
+use rustdoc_code_formatter::{SemverImpact, semver_check};
+
+fn example_api(version: &str) -> rustdoc_code_formatter::ModuleRepr {
+    let manifest_path = format!(
+        "{}/examples/test-apis/example_api-v{version}/Cargo.toml",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    rustdoc_code_formatter::build(&manifest_path).remove(0)
+}
+
+#[test]
+fn example_api_changes() {
+    let report = semver_check(&example_api("0.1.0"), &example_api("0.2.0"));
+    let changes: Vec<_> = report
+        .changes
+        .iter()
+        .map(|change| (change.path.join("::"), change.impact))
+        .collect();
+    // Renaming arguments and generic params of `renamed` and `Trait::generic` isn't a change
+    assert_eq!(
+        changes,
+        [
+            ("example_api::Struct".to_string(), SemverImpact::Major),
+            ("example_api::StructV2".to_string(), SemverImpact::Minor),
+            (
+                "example_api::Trait::added_required".to_string(),
+                SemverImpact::Major
+            ),
+            ("example_api::function".to_string(), SemverImpact::Major),
+            ("example_api::removed".to_string(), SemverImpact::Major),
+        ]
+    );
+    assert_eq!(report.next_version("0.1.0").as_deref(), Some("0.2.0"));
+}
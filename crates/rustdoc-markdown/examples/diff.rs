@@ -0,0 +1,17 @@
+use std::io;
+
+fn main() {
+    let old = rustdoc_code_formatter::build(
+        "./crates/rustdoc-code-formatter/examples/test-apis/example_api-v0.1.0/Cargo.toml",
+    );
+    let new = rustdoc_code_formatter::build(
+        "./crates/rustdoc-code-formatter/examples/test-apis/example_api-v0.2.0/Cargo.toml",
+    );
+    let diff = rustdoc_code_formatter::diff(&old[0], &new[0]);
+    rustdoc_markdown::write_diff(&diff, io::stdout()).unwrap();
+
+    let report = rustdoc_code_formatter::semver_check(&old[0], &new[0]);
+    rustdoc_markdown::write_semver_report(&report, io::stdout()).unwrap();
+    println!("{:#}", report.to_json());
+    println!("Next version: {}", report.next_version("0.1.0").unwrap());
+}
@@ -1,28 +1,47 @@
 use std::fs::{self, File};
 use std::path::PathBuf;
 
-use rustdoc_code_formatter::ModuleRepr;
+use rustdoc_code_formatter::{Builder, ModuleRepr, Visitor, visit};
+use rustdoc_markdown::{FrontMatter, FrontMatterFormat, Options, SourceLinks, TocOptions};
 
 fn main() {
-    let modules = rustdoc_code_formatter::build(
-        "./crates/rustdoc-code-formatter/examples/test-apis/test_api/Cargo.toml",
-    );
-    fs::create_dir_all("./out").unwrap();
-    for module in modules {
-        write_module(&module);
+    let modules = Builder::default()
+        .manifest_path("./crates/rustdoc-code-formatter/examples/test-apis/test_api/Cargo.toml")
+        .cache_dir("./target/rustdoc-cache")
+        .build();
+    let source_links =
+        SourceLinks::new("https://github.com/{repo}/blob/{rev}/{path}#L{line}-L{end}")
+            .repo("aschey/rustdoc-markdown")
+            .git("./crates/rustdoc-code-formatter/examples/test-apis/test_api")
+            .unwrap();
+    let options = Options::default()
+        .source_links(source_links)
+        .toc(TocOptions::default())
+        .front_matter(FrontMatter::new(FrontMatterFormat::Yaml).entry("tags", vec!["api"]))
+        .workspace_links(&modules);
+    let mut writer = ModuleWriter { options: &options };
+    for module in &modules {
+        writer.visit_module(module);
+
+        // The whole crate on one page as well
+        let file = File::create(format!("./out/{}.all.md", module.name)).unwrap();
+        rustdoc_markdown::write_single_file(module, file, &options).unwrap();
     }
 }
 
-fn write_module(module: &ModuleRepr) {
-    let file = File::options()
-        .write(true)
-        .create(true)
-        .open(format!("./out/{}.md", module.name))
-        .unwrap();
+struct ModuleWriter<'a> {
+    options: &'a Options,
+}
 
-    rustdoc_markdown::write(module, file).unwrap();
+impl Visitor<'_> for ModuleWriter<'_> {
+    fn visit_module(&mut self, module: &ModuleRepr) {
+        // Every crate in a workspace shares the output directory, so files are named by their full
+        // path
+        let path = PathBuf::from("./out").join(rustdoc_markdown::page_path(&module.path));
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let file = File::create(path).unwrap();
 
-    for module in &module.modules {
-        write_module(module);
+        rustdoc_markdown::write_with_options(module, file, self.options).unwrap();
+        visit::walk_module(self, module);
     }
 }
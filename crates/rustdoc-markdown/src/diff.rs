@@ -0,0 +1,167 @@
+use std::io::{self, Write};
+
+use markdown_writer::{HeaderLevel, MarkdownWriter, code_span};
+use rustdoc_code_formatter::{ApiDiff, ChangedItem, DiffItem};
+
+/// Writes a changelog section listing added, removed and changed items
+pub fn write_diff<W: io::Write>(diff: &ApiDiff, writer: W) -> io::Result<()> {
+    let mut writer = MarkdownWriter::new(writer);
+
+    writer.header(HeaderLevel::Two, "API changes")?;
+    writer.newlines(2)?;
+    if diff.is_empty() {
+        write!(writer, "No changes to the public API.")?;
+        return writer.newline();
+    }
+
+    write_items(&mut writer, "Added", &diff.added)?;
+    write_items(&mut writer, "Removed", &diff.removed)?;
+
+    if !diff.changed.is_empty() {
+        writer.header(HeaderLevel::Three, "Changed")?;
+        writer.newlines(2)?;
+        for item in &diff.changed {
+            write_changed(&mut writer, item)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_items<W: io::Write>(
+    writer: &mut MarkdownWriter<W>,
+    title: &str,
+    items: &[DiffItem],
+) -> io::Result<()> {
+    if items.is_empty() {
+        return Ok(());
+    }
+    writer.header(HeaderLevel::Three, title)?;
+    writer.newlines(2)?;
+    for item in items {
+        writer.header(
+            HeaderLevel::Four,
+            &format!("{} ({})", code_span(&item.path.join("::")), item.kind),
+        )?;
+        writer.newlines(2)?;
+        writer.fenced_code_block(&item.signature, Some("rust"))?;
+        writer.newlines(2)?;
+    }
+    Ok(())
+}
+
+fn write_changed<W: io::Write>(
+    writer: &mut MarkdownWriter<W>,
+    item: &ChangedItem,
+) -> io::Result<()> {
+    writer.header(
+        HeaderLevel::Four,
+        &format!("{} ({})", code_span(&item.path.join("::")), item.kind),
+    )?;
+    writer.newlines(2)?;
+    // A diff block shows both versions while keeping them easy to compare
+    let mut lines = Vec::new();
+    for line in item.before.lines() {
+        lines.push(format!("- {line}"));
+    }
+    for line in item.after.lines() {
+        lines.push(format!("+ {line}"));
+    }
+    writer.fenced_code_block(&lines.join("\n"), Some("diff"))?;
+    writer.newlines(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use rustdoc_code_formatter::{ApiDiff, ChangedItem, DiffItem, ItemKind};
+
+    use super::write_diff;
+
+    fn path(path: &str) -> Vec<String> {
+        path.split("::").map(str::to_string).collect()
+    }
+
+    fn write(diff: &ApiDiff) -> String {
+        let mut output = Vec::new();
+        write_diff(diff, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn no_changes() {
+        assert_eq!(
+            write(&ApiDiff::default()),
+            "## API changes\n\nNo changes to the public API.\n"
+        );
+    }
+
+    #[test]
+    fn sections() {
+        let diff = ApiDiff {
+            added: vec![DiffItem {
+                path: path("api::added"),
+                kind: ItemKind::Fn,
+                signature: "pub fn added()".to_string(),
+            }],
+            removed: vec![DiffItem {
+                path: path("api::Removed"),
+                kind: ItemKind::Struct,
+                signature: "pub struct Removed;".to_string(),
+            }],
+            changed: vec![ChangedItem {
+                path: path("api::T"),
+                kind: ItemKind::Trait,
+                before: "pub trait T {".to_string(),
+                after: "pub trait T\nwhere\n    Self: Sized,\n{".to_string(),
+            }],
+        };
+        let expected = [
+            "## API changes",
+            "",
+            "### Added",
+            "",
+            "#### `api::added` (function)",
+            "",
+            "```rust",
+            "pub fn added()",
+            "```",
+            "",
+            "### Removed",
+            "",
+            "#### `api::Removed` (struct)",
+            "",
+            "```rust",
+            "pub struct Removed;",
+            "```",
+            "",
+            "### Changed",
+            "",
+            "#### `api::T` (trait)",
+            "",
+            "```diff",
+            "- pub trait T {",
+            "+ pub trait T",
+            "+ where",
+            "+     Self: Sized,",
+            "+ {",
+            "```",
+            "",
+        ];
+        assert_eq!(write(&diff), expected.join("\n") + "\n");
+    }
+
+    #[test]
+    fn empty_sections_are_left_out() {
+        let diff = ApiDiff {
+            added: vec![DiffItem {
+                path: path("api::a"),
+                kind: ItemKind::Fn,
+                signature: "pub fn a()".to_string(),
+            }],
+            ..ApiDiff::default()
+        };
+        let output = write(&diff);
+        assert!(output.contains("### Added"), "{output}");
+        assert!(!output.contains("### Removed"), "{output}");
+        assert!(!output.contains("### Changed"), "{output}");
+    }
+}
@@ -1,40 +1,351 @@
-use std::io;
+mod diff;
+mod semver;
+mod source_links;
+mod workspace_links;
 
-use markdown_writer::{HeaderLevel, MarkdownWriter};
-use rustdoc_code_formatter::ModuleRepr;
+use std::io::{self, Write};
+
+pub use diff::write_diff;
+use markdown_writer::{AdmonitionKind, HeaderLevel, MarkdownWriter, code_span, insert_toc};
+pub use markdown_writer::{Flavor, FrontMatter, FrontMatterFormat, TocOptions};
+use rustdoc_code_formatter::{ItemKind, ModuleRepr, SourceSpan, TraitItemKind, TraitRepr, TypeRef};
+pub use semver::write_semver_report;
+pub use source_links::SourceLinks;
+use workspace_links::WorkspaceLinks;
+
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    source_links: Option<SourceLinks>,
+    toc: Option<TocOptions>,
+    flavor: Flavor,
+    front_matter: Option<FrontMatter>,
+    workspace_links: Option<WorkspaceLinks>,
+}
+
+impl Options {
+    /// Adds a link to the source next to each item
+    pub fn source_links(mut self, source_links: SourceLinks) -> Self {
+        self.source_links = Some(source_links);
+        self
+    }
+
+    /// Adds a table of contents below the page title. The page is buffered until it's complete.
+    pub fn toc(mut self, toc: TocOptions) -> Self {
+        self.toc = Some(toc);
+        self
+    }
+
+    /// The markdown dialect of the pages, GFM by default
+    pub fn flavor(mut self, flavor: Flavor) -> Self {
+        self.flavor = flavor;
+        self
+    }
+
+    /// Starts every page with front matter in the format of `front_matter`. `title`, `crate`,
+    /// `module` and `kind` are filled in for each module, entries in `front_matter` are added to
+    /// them or override them.
+    pub fn front_matter(mut self, front_matter: FrontMatter) -> Self {
+        self.front_matter = Some(front_matter);
+        self
+    }
+
+    /// Links the types in signatures that are documented in another of `crates` to their pages.
+    /// The links assume every module is written to [`page_path`] and single file pages are next to
+    /// the crate root's page.
+    pub fn workspace_links(mut self, crates: &[ModuleRepr]) -> Self {
+        self.workspace_links = Some(WorkspaceLinks::new(crates));
+        self
+    }
+}
+
+/// Where the page of the module at `module_path` goes relative to the output root, e.g.
+/// `my_crate/module.md` for `my_crate::module`
+pub fn page_path(module_path: &[String]) -> String {
+    format!("{}.md", module_path.join("/"))
+}
 
 pub fn write<W: io::Write>(module: &ModuleRepr, writer: W) -> io::Result<()> {
-    let mut writer = MarkdownWriter::new(writer);
+    write_with_options(module, writer, &Options::default())
+}
 
-    writer.header(HeaderLevel::One, "Docs")?;
+/// Writes a page for `module` alone, submodules need pages of their own
+pub fn write_with_options<W: io::Write>(
+    module: &ModuleRepr,
+    writer: W,
+    options: &Options,
+) -> io::Result<()> {
+    write_page(writer, module, options, |writer| {
+        let depth = module.path.len() - 1;
+        write_module(writer, module, options, HeaderLevel::Two, depth, "")
+    })
+}
+
+/// Writes a single page for `module` and all of its submodules, each in a section headed by its
+/// path
+pub fn write_single_file<W: io::Write>(
+    module: &ModuleRepr,
+    writer: W,
+    options: &Options,
+) -> io::Result<()> {
+    write_page(writer, module, options, |writer| {
+        write_module_tree(writer, module, options)
+    })
+}
+
+fn write_module_tree<W: io::Write>(
+    writer: &mut MarkdownWriter<W>,
+    module: &ModuleRepr,
+    options: &Options,
+) -> io::Result<()> {
+    writer.header(HeaderLevel::Two, &code_span(&module.path.join("::")))?;
     writer.newlines(2)?;
+    // Every module shares the page, so item anchors are prefixed with the module path
+    let anchor_prefix = format!("{}.", module.path.join("."));
+    write_module(
+        writer,
+        module,
+        options,
+        HeaderLevel::Three,
+        0,
+        &anchor_prefix,
+    )?;
+    for module in &module.modules {
+        write_module_tree(writer, module, options)?;
+    }
+    Ok(())
+}
+
+fn write_page<W: io::Write>(
+    mut writer: W,
+    module: &ModuleRepr,
+    options: &Options,
+    body: impl FnOnce(&mut MarkdownWriter<&mut dyn io::Write>) -> io::Result<()>,
+) -> io::Result<()> {
+    let Some(toc_options) = options.toc else {
+        let mut page =
+            MarkdownWriter::with_flavor(&mut writer as &mut dyn io::Write, options.flavor);
+        write_front_matter(&mut page, module, options)?;
+        page.header(HeaderLevel::One, "Docs")?;
+        page.newlines(2)?;
+        return body(&mut page);
+    };
+
+    // The table of contents can only be written once every header is known
+    let mut buffer = Vec::new();
+    let mut page = MarkdownWriter::with_flavor(&mut buffer as &mut dyn io::Write, options.flavor);
+    write_front_matter(&mut page, module, options)?;
+    page.header(HeaderLevel::One, "Docs")?;
+    page.newlines(2)?;
+    page.toc_placeholder()?;
+    page.newlines(2)?;
+    body(&mut page)?;
+    let toc = page.toc(&toc_options);
 
-    writer.header(HeaderLevel::Two, "Functions")?;
+    let page = String::from_utf8(buffer).unwrap();
+    writer.write_all(insert_toc(&page, &toc, options.flavor).as_bytes())
+}
+
+fn write_front_matter<W: io::Write>(
+    writer: &mut MarkdownWriter<W>,
+    module: &ModuleRepr,
+    options: &Options,
+) -> io::Result<()> {
+    let Some(extra) = &options.front_matter else {
+        return Ok(());
+    };
+    let path = module.path.join("::");
+    let kind = if module.path.len() == 1 {
+        "crate"
+    } else {
+        "module"
+    };
+    let mut front_matter = FrontMatter::new(extra.format)
+        .entry("title", path.as_str())
+        .entry("crate", module.path[0].as_str())
+        .entry("module", path)
+        .entry("kind", kind);
+    for (key, value) in &extra.entries {
+        front_matter = front_matter.entry(key.as_str(), value.clone());
+    }
+    writer.front_matter(&front_matter)?;
+    writer.newline()
+}
+
+/// Where the header of an item on the page of its module can be linked to, e.g. `struct.Config`.
+/// Unlike the anchors generated from header text, it can't collide with other headers.
+pub(crate) fn item_anchor(kind: ItemKind, name: &str) -> String {
+    let kind = match kind {
+        ItemKind::Module => "mod",
+        ItemKind::Fn => "fn",
+        ItemKind::Struct => "struct",
+        ItemKind::Trait => "trait",
+        ItemKind::TraitItem(TraitItemKind::Fn) => "method",
+        ItemKind::TraitItem(TraitItemKind::AssocConst) => "associatedconstant",
+        ItemKind::TraitItem(TraitItemKind::AssocType) => "associatedtype",
+    };
+    format!("{kind}.{name}")
+}
+
+/// Writes the sections for the items of `module` with their headers at `level`, on a page `depth`
+/// directories below the output root. Item anchors start with `anchor_prefix`.
+fn write_module<W: io::Write>(
+    writer: &mut MarkdownWriter<W>,
+    module: &ModuleRepr,
+    options: &Options,
+    level: HeaderLevel,
+    depth: usize,
+    anchor_prefix: &str,
+) -> io::Result<()> {
+    let anchor = |kind, name| format!("{anchor_prefix}{}", item_anchor(kind, name));
+    writer.header(level, "Functions")?;
     writer.newlines(2)?;
     for function in &module.functions {
-        writer.header(HeaderLevel::Three, &function.name)?;
+        writer.anchor_header(
+            level.nested(),
+            &function.name,
+            &anchor(ItemKind::Fn, &function.name),
+        )?;
         writer.newlines(2)?;
         writer.fenced_code_block(&function.repr, Some("rust"))?;
         writer.newlines(2)?;
+        if function.is_unsafe {
+            writer.admonition(
+                AdmonitionKind::Warning,
+                "This function is `unsafe`: callers must uphold its safety requirements.",
+            )?;
+            writer.newlines(1)?;
+        }
+        let types = function.input_types.iter().chain(&function.output_types);
+        write_workspace_links(writer, options, module, depth, types)?;
+        write_source_link(writer, options, function.span.as_ref())?;
     }
 
-    writer.header(HeaderLevel::Two, "Structs")?;
+    writer.header(level, "Structs")?;
     writer.newlines(2)?;
     for struct_ in &module.structs {
-        writer.header(HeaderLevel::Three, &struct_.name)?;
+        writer.anchor_header(
+            level.nested(),
+            &struct_.name,
+            &anchor(ItemKind::Struct, &struct_.name),
+        )?;
         writer.newlines(2)?;
         writer.fenced_code_block(&struct_.repr, Some("rust"))?;
         writer.newlines(2)?;
+        write_workspace_links(writer, options, module, depth, &struct_.field_types)?;
+        write_source_link(writer, options, struct_.span.as_ref())?;
     }
 
-    writer.header(HeaderLevel::Two, "Traits")?;
+    writer.header(level, "Traits")?;
     writer.newlines(2)?;
     for trait_ in &module.traits {
-        writer.header(HeaderLevel::Three, &trait_.name)?;
+        writer.anchor_header(
+            level.nested(),
+            &trait_.name,
+            &anchor(ItemKind::Trait, &trait_.name),
+        )?;
         writer.newlines(2)?;
         writer.fenced_code_block(&trait_.repr, Some("rust"))?;
         writer.newlines(2)?;
+        let types = trait_
+            .items
+            .iter()
+            .flat_map(|item| item.input_types.iter().chain(&item.output_types));
+        write_workspace_links(writer, options, module, depth, types)?;
+        write_source_link(writer, options, trait_.span.as_ref())?;
+        write_dyn_compatibility(writer, trait_)?;
+        write_trait_methods(writer, trait_, level.nested().nested(), true)?;
+        write_trait_methods(writer, trait_, level.nested().nested(), false)?;
+    }
+
+    Ok(())
+}
+
+fn write_workspace_links<'a, W: io::Write>(
+    writer: &mut MarkdownWriter<W>,
+    options: &Options,
+    module: &ModuleRepr,
+    depth: usize,
+    types: impl IntoIterator<Item = &'a TypeRef>,
+) -> io::Result<()> {
+    let Some(workspace_links) = &options.workspace_links else {
+        return Ok(());
+    };
+    let mut links: Vec<(String, String)> = Vec::new();
+    for type_ref in types {
+        let Some(url) = workspace_links.url(&module.path[0], depth, type_ref) else {
+            continue;
+        };
+        let path = type_ref.path.join("::");
+        if !links.iter().any(|(existing, _)| *existing == path) {
+            links.push((path, url));
+        }
+    }
+    if links.is_empty() {
+        return Ok(());
     }
 
+    writer.bold("Workspace types:")?;
+    for (i, (path, url)) in links.iter().enumerate() {
+        write!(writer, "{}", if i == 0 { " " } else { ", " })?;
+        writer.link(path, url)?;
+    }
+    writer.newlines(2)
+}
+
+fn write_source_link<W: io::Write>(
+    writer: &mut MarkdownWriter<W>,
+    options: &Options,
+    span: Option<&SourceSpan>,
+) -> io::Result<()> {
+    let (Some(source_links), Some(span)) = (&options.source_links, span) else {
+        return Ok(());
+    };
+    writer.link("source", &source_links.url(span))?;
+    writer.newlines(2)
+}
+
+fn write_dyn_compatibility<W: io::Write>(
+    writer: &mut MarkdownWriter<W>,
+    trait_: &TraitRepr,
+) -> io::Result<()> {
+    writer.bold("Dyn compatibility:")?;
+    if trait_.dyn_compatible {
+        write!(writer, " This trait is dyn compatible.")?;
+    } else {
+        write!(
+            writer,
+            " This trait is not dyn compatible, so it can't be used as `dyn {}`.",
+            trait_.name
+        )?;
+    }
+    writer.newlines(2)
+}
+
+fn write_trait_methods<W: io::Write>(
+    writer: &mut MarkdownWriter<W>,
+    trait_: &TraitRepr,
+    level: HeaderLevel,
+    required: bool,
+) -> io::Result<()> {
+    let methods: Vec<_> = trait_
+        .items
+        .iter()
+        .filter(|item| item.kind == TraitItemKind::Fn && item.required == required)
+        .collect();
+    if methods.is_empty() {
+        return Ok(());
+    }
+
+    let title = if required {
+        "Required methods"
+    } else {
+        "Provided methods"
+    };
+    writer.header(level, title)?;
+    writer.newlines(2)?;
+    for method in methods {
+        writer.fenced_code_block(&method.repr, Some("rust"))?;
+        writer.newlines(2)?;
+    }
     Ok(())
 }
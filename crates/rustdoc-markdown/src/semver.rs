@@ -0,0 +1,48 @@
+use std::io::{self, Write};
+
+use markdown_writer::{HeaderLevel, List, MarkdownWriter, code_span};
+use rustdoc_code_formatter::{SemverImpact, SemverReport};
+
+/// Writes the overall semver impact followed by every change grouped by its impact
+pub fn write_semver_report<W: io::Write>(report: &SemverReport, writer: W) -> io::Result<()> {
+    let mut writer = MarkdownWriter::new(writer);
+
+    writer.header(
+        HeaderLevel::Two,
+        &format!("Semver impact: {}", report.impact()),
+    )?;
+    writer.newlines(2)?;
+    if report.changes.is_empty() {
+        write!(writer, "No changes to the public API.")?;
+        return writer.newline();
+    }
+
+    for (impact, title) in [
+        (SemverImpact::Major, "Breaking changes"),
+        (SemverImpact::Minor, "Compatible additions"),
+        (SemverImpact::Patch, "Other changes"),
+    ] {
+        let changes: Vec<_> = report
+            .changes
+            .iter()
+            .filter(|change| change.impact == impact)
+            .collect();
+        if changes.is_empty() {
+            continue;
+        }
+        writer.header(HeaderLevel::Three, title)?;
+        writer.newlines(2)?;
+        let mut list = List::bullet();
+        for change in changes {
+            list = list.item(format!(
+                "{} ({}): {}",
+                code_span(&change.path.join("::")),
+                change.kind,
+                change.reason
+            ));
+        }
+        writer.list(&list)?;
+        writer.newline()?;
+    }
+    Ok(())
+}
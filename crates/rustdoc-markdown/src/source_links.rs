@@ -0,0 +1,141 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use rustdoc_code_formatter::SourceSpan;
+
+/// Builds links to the source of each item on a repository host from a URL template.
+///
+/// The template can contain these placeholders:
+///
+/// - `{repo}`: the repository, e.g. `owner/name`
+/// - `{rev}`: the commit, branch or tag to link to
+/// - `{path}`: the file path relative to the repository root
+/// - `{line}` and `{end}`: the first and last line of the item
+///
+/// For example `https://github.com/{repo}/blob/{rev}/{path}#L{line}-L{end}`.
+#[derive(Debug, Clone)]
+pub struct SourceLinks {
+    template: String,
+    repo: String,
+    rev: String,
+    workspace_dir: PathBuf,
+}
+
+impl SourceLinks {
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+            repo: String::new(),
+            rev: "HEAD".to_string(),
+            workspace_dir: PathBuf::new(),
+        }
+    }
+
+    pub fn repo(mut self, repo: impl Into<String>) -> Self {
+        self.repo = repo.into();
+        self
+    }
+
+    pub fn rev(mut self, rev: impl Into<String>) -> Self {
+        self.rev = rev.into();
+        self
+    }
+
+    /// Root of the documented crate's workspace relative to the repository root. Spans are
+    /// relative to the workspace root, or the crate's directory if it isn't part of a workspace,
+    /// so this is prepended to every path.
+    pub fn workspace_dir(mut self, workspace_dir: impl AsRef<Path>) -> Self {
+        self.workspace_dir = workspace_dir.as_ref().to_owned();
+        self
+    }
+
+    /// Takes the revision from the current git HEAD and the workspace directory from its position
+    /// in the work tree. `dir` is the directory of the documented crate or its workspace.
+    pub fn git(self, dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref();
+        let rev = run(dir, "git", &["rev-parse", "HEAD"])?;
+        let workspace_manifest = run(
+            dir,
+            "cargo",
+            &["locate-project", "--workspace", "--message-format", "plain"],
+        )?;
+        let workspace_root = Path::new(&workspace_manifest).parent().unwrap();
+        let prefix = run(workspace_root, "git", &["rev-parse", "--show-prefix"])?;
+        Ok(self.rev(rev).workspace_dir(prefix))
+    }
+
+    pub fn url(&self, span: &SourceSpan) -> String {
+        // Links always use forward slashes, even if the docs were built on Windows
+        let path = self
+            .workspace_dir
+            .join(&span.filename)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        self.template
+            .replace("{repo}", &self.repo)
+            .replace("{rev}", &self.rev)
+            .replace("{path}", &path)
+            .replace("{line}", &span.start_line.to_string())
+            .replace("{end}", &span.end_line.to_string())
+    }
+}
+
+fn run(dir: &Path, program: &str, args: &[&str]) -> io::Result<String> {
+    let output = Command::new(program).args(args).current_dir(dir).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rustdoc_code_formatter::SourceSpan;
+
+    use super::SourceLinks;
+
+    const TEMPLATE: &str = "https://github.com/{repo}/blob/{rev}/{path}#L{line}-L{end}";
+
+    fn span(filename: &str) -> SourceSpan {
+        SourceSpan {
+            filename: PathBuf::from(filename),
+            start_line: 3,
+            start_column: 1,
+            end_line: 5,
+            end_column: 2,
+        }
+    }
+
+    #[test]
+    fn prepends_workspace_dir() {
+        let links = SourceLinks::new(TEMPLATE)
+            .repo("owner/name")
+            .rev("main")
+            .workspace_dir("nested/");
+        assert_eq!(
+            links.url(&span("crates/member/src/lib.rs")),
+            "https://github.com/owner/name/blob/main/nested/crates/member/src/lib.rs#L3-L5"
+        );
+    }
+
+    // rustdoc makes spans of workspace members relative to the workspace root, so the member's
+    // own directory must not be prepended again
+    #[test]
+    fn git_uses_workspace_root_for_members() {
+        let member_dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../markdown-writer");
+        let links = SourceLinks::new(TEMPLATE).git(member_dir).unwrap();
+        let url = links.url(&span("crates/markdown-writer/src/lib.rs"));
+        assert!(
+            url.ends_with("/crates/markdown-writer/src/lib.rs#L3-L5"),
+            "{url}"
+        );
+        assert!(!url.contains("markdown-writer/crates"), "{url}");
+    }
+}
@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+use rustdoc_code_formatter::{ItemKind, ModuleRepr, TypeRef};
+
+use crate::{item_anchor, page_path};
+
+/// Finds the pages of the items documented for every crate of a workspace
+#[derive(Debug, Clone, Default)]
+pub(crate) struct WorkspaceLinks {
+    /// Path of each function, struct and trait to its kind and the path of the module it's
+    /// documented in
+    modules: HashMap<Vec<String>, (ItemKind, Vec<String>)>,
+}
+
+impl WorkspaceLinks {
+    pub(crate) fn new(crates: &[ModuleRepr]) -> Self {
+        let mut modules = HashMap::new();
+        for crate_ in crates {
+            for item in crate_.items() {
+                if matches!(
+                    item.kind(),
+                    ItemKind::Fn | ItemKind::Struct | ItemKind::Trait
+                ) {
+                    let path = item.path();
+                    modules.insert(
+                        path.to_vec(),
+                        (item.kind(), path[..path.len() - 1].to_vec()),
+                    );
+                }
+            }
+        }
+        Self { modules }
+    }
+
+    /// The URL of the item `type_ref` points to if it's documented in another crate than
+    /// `crate_name`, relative to a page `depth` directories below the output root
+    pub(crate) fn url(&self, crate_name: &str, depth: usize, type_ref: &TypeRef) -> Option<String> {
+        if type_ref.path.first().map(String::as_str) == Some(crate_name) {
+            return None;
+        }
+        let (kind, module) = self.modules.get(&type_ref.path)?;
+        Some(format!(
+            "{}{}#{}",
+            "../".repeat(depth),
+            page_path(module),
+            item_anchor(*kind, type_ref.path.last()?)
+        ))
+    }
+}
@@ -0,0 +1,55 @@
+use rustdoc_code_formatter::{Builder, ModuleRepr};
+use rustdoc_markdown::{Flavor, Options};
+
+const TEST_API: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../rustdoc-code-formatter/examples/test-apis/test_api/Cargo.toml"
+);
+
+fn functions() -> ModuleRepr {
+    let mut crates = Builder::default().manifest_path(TEST_API).build();
+    let root = crates.remove(0);
+    root.modules
+        .into_iter()
+        .find(|module| module.name == "functions")
+        .unwrap()
+}
+
+fn write_page(module: &ModuleRepr, flavor: Flavor) -> String {
+    let mut page = Vec::new();
+    rustdoc_markdown::write_with_options(module, &mut page, &Options::default().flavor(flavor))
+        .unwrap();
+    String::from_utf8(page).unwrap()
+}
+
+#[test]
+fn warns_about_unsafe_fns() {
+    let functions = functions();
+    let page = write_page(&functions, Flavor::Gfm);
+    let warning = [
+        "```rust",
+        "pub unsafe fn unsafe_fn()",
+        "```",
+        "",
+        "> [!WARNING]",
+        "> This function is `unsafe`: callers must uphold its safety requirements.",
+        "",
+    ]
+    .join("\n");
+    assert!(page.contains(&warning), "{page}");
+    // Only for the one `unsafe fn`
+    assert_eq!(page.matches("[!WARNING]").count(), 1, "{page}");
+}
+
+#[test]
+fn warning_follows_the_flavor() {
+    let functions = functions();
+    let page = write_page(&functions, Flavor::Mdx);
+    let warning = [
+        ":::warning",
+        "This function is `unsafe`: callers must uphold its safety requirements.",
+        ":::",
+    ]
+    .join("\n");
+    assert!(page.contains(&warning), "{page}");
+}
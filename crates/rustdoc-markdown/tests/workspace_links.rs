@@ -0,0 +1,132 @@
+use rustdoc_code_formatter::{Builder, ModuleRepr};
+use rustdoc_markdown::Options;
+
+const WORKSPACE_API: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../rustdoc-code-formatter/examples/test-apis/workspace_api/Cargo.toml"
+);
+
+fn write_page(crates: &[ModuleRepr], module: &ModuleRepr, options: &Options) -> String {
+    let mut page = Vec::new();
+    rustdoc_markdown::write_with_options(
+        module,
+        &mut page,
+        &options.clone().workspace_links(crates),
+    )
+    .unwrap();
+    String::from_utf8(page).unwrap()
+}
+
+#[test]
+fn links_types_from_other_members() {
+    let crates = Builder::default().manifest_path(WORKSPACE_API).build();
+    let app = crates
+        .iter()
+        .find(|module| module.name == "app_api")
+        .unwrap();
+    let page = write_page(&crates, app, &Options::default());
+
+    assert!(
+        page.contains(r"[core\_api::config::Config](core_api/config.md#struct.Config)"),
+        "{page}"
+    );
+    assert!(
+        page.contains(r"[core\_api::Output](core_api.md#struct.Output)"),
+        "{page}"
+    );
+    // Shown with the path of the re-export, which isn't documented so there's no page to link to
+    assert!(
+        page.contains("pub fn hidden(hidden: core_api::Hidden)"),
+        "{page}"
+    );
+    assert!(!page.contains("inner"), "{page}");
+}
+
+#[test]
+fn links_to_item_anchors() {
+    let crates = Builder::default().manifest_path(WORKSPACE_API).build();
+    let [app, core] = &crates[..] else {
+        panic!("{crates:?}")
+    };
+    let page = write_page(&crates, app, &Options::default());
+    // `fn builder` comes first and would get the `builder` anchor
+    assert!(
+        page.contains(r"[core\_api::Builder](core_api.md#struct.Builder)"),
+        "{page}"
+    );
+    assert!(
+        page.contains(r"[core\_api::Structs](core_api.md#struct.Structs)"),
+        "{page}"
+    );
+
+    let page = write_page(&crates, core, &Options::default());
+    assert!(
+        page.contains("<a name=\"fn.builder\"></a>\n### builder"),
+        "{page}"
+    );
+    assert!(
+        page.contains("<a name=\"struct.Builder\"></a>\n### Builder"),
+        "{page}"
+    );
+    assert!(
+        page.contains("<a name=\"struct.Structs\"></a>\n### Structs"),
+        "{page}"
+    );
+}
+
+#[test]
+fn single_file_anchors_include_the_module() {
+    let crates = Builder::default().manifest_path(WORKSPACE_API).build();
+    let core = crates
+        .iter()
+        .find(|module| module.name == "core_api")
+        .unwrap();
+    let mut page = Vec::new();
+    rustdoc_markdown::write_single_file(core, &mut page, &Options::default()).unwrap();
+    let page = String::from_utf8(page).unwrap();
+    assert!(
+        page.contains("<a name=\"core_api.struct.Output\"></a>"),
+        "{page}"
+    );
+    assert!(
+        page.contains("<a name=\"core_api.config.struct.Config\"></a>"),
+        "{page}"
+    );
+}
+
+#[test]
+fn links_are_relative_to_nested_pages() {
+    let crates = Builder::default().manifest_path(WORKSPACE_API).build();
+    let app = crates
+        .iter()
+        .find(|module| module.name == "app_api")
+        .unwrap();
+    let page = write_page(&crates, &app.modules[0], &Options::default());
+    assert!(
+        page.contains(r"[core\_api::config::Config](../core_api/config.md#struct.Config)"),
+        "{page}"
+    );
+}
+
+#[test]
+fn types_from_the_same_crate_are_not_linked() {
+    let crates = Builder::default().manifest_path(WORKSPACE_API).build();
+    let core = crates
+        .iter()
+        .find(|module| module.name == "core_api")
+        .unwrap();
+    let page = write_page(&crates, &core.modules[0], &Options::default());
+    assert!(!page.contains("Workspace types"), "{page}");
+}
+
+#[test]
+fn no_links_without_workspace() {
+    let crates = Builder::default().manifest_path(WORKSPACE_API).build();
+    let app = crates
+        .iter()
+        .find(|module| module.name == "app_api")
+        .unwrap();
+    let mut page = Vec::new();
+    rustdoc_markdown::write(app, &mut page).unwrap();
+    assert!(!String::from_utf8(page).unwrap().contains("Workspace types"));
+}